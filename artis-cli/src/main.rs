@@ -0,0 +1,197 @@
+use std::path::{Path, PathBuf};
+
+use artis::{
+    migrator::{
+        diff, from_snapshot, generate, to_snapshot, DriverMigrator, Migration, MysqlMigrator, Plan,
+        PostgresMigrator, SqliteMigrator, TableMeta,
+    },
+    Artis, Result,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use rbdc_mysql::MysqlDriver;
+use rbdc_pg::PostgresDriver;
+use rbdc_sqlite::SqliteDriver;
+
+#[derive(Debug, Parser)]
+#[command(name = "artis", version, about = "Artis migrations and introspection")]
+struct Cli {
+    /// Database url: sqlite://path, mysql://... or postgres://...
+    #[arg(long, env = "DATABASE_URL", global = true)]
+    url: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    #[command(flatten)]
+    Db(DbCommand),
+    /// Compare two snapshot files, exits with 1 when they differ
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+// Commands that need a database connection
+#[derive(Debug, Subcommand)]
+enum DbCommand {
+    /// Apply versioned migrations from a directory, or a declarative snapshot
+    Migrate {
+        /// Directory of {version}_{name}.up.sql / .down.sql files
+        #[arg(
+            long,
+            conflicts_with = "snapshot",
+            required_unless_present = "snapshot"
+        )]
+        dir: Option<PathBuf>,
+        /// Snapshot file to migrate the database to
+        #[arg(long)]
+        snapshot: Option<PathBuf>,
+        /// Roll back the last N versioned migrations
+        #[arg(long, requires = "dir")]
+        down: Option<usize>,
+    },
+    /// Print the statements a declarative migration would run
+    Plan {
+        /// Snapshot file to plan against
+        #[arg(long)]
+        snapshot: PathBuf,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Dump the database schema
+    Introspect {
+        #[arg(long, value_enum, default_value = "json")]
+        format: Format,
+    },
+    /// Show the status of versioned migrations
+    Status {
+        /// Directory of {version}_{name}.up.sql / .down.sql files
+        #[arg(long)]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum Format {
+    Json,
+    Rust,
+}
+
+fn read_snapshot(path: &Path) -> Result<Vec<TableMeta>> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    from_snapshot(&raw)
+}
+
+fn print_plans(plans: &[Plan], json: bool) -> Result<()> {
+    if json {
+        let raw = serde_json::to_string_pretty(plans).map_err(|e| e.to_string())?;
+        println!("{}", raw);
+        return Ok(());
+    }
+    for v in plans.iter() {
+        let flag = if v.destructive { " (destructive)" } else { "" };
+        println!("-- {}: {}{}", v.table, v.change, flag);
+        for raw in v.raws.iter() {
+            println!("{};", raw);
+        }
+    }
+    Ok(())
+}
+
+async fn run<M>(rb: Artis, m: M, command: DbCommand) -> Result<()>
+where
+    M: for<'a> DriverMigrator<'a>,
+{
+    match command {
+        DbCommand::Migrate {
+            dir: Some(dir),
+            down,
+            ..
+        } => {
+            let list = Migration::from_dir(dir)?;
+            let versions = match down {
+                Some(n) => rb.migrate_down(&m, &list, n).await?,
+                None => rb.migrate_up(&m, &list).await?,
+            };
+            println!("migrated versions: {:?}", versions);
+        }
+        DbCommand::Migrate { snapshot, .. } => {
+            let path = snapshot.ok_or("missing --dir or --snapshot")?;
+            rb.auto_migrate(&m, read_snapshot(&path)?).await?;
+        }
+        DbCommand::Plan { snapshot, json } => {
+            let plans = rb.plan_migrate(&m, read_snapshot(&snapshot)?).await?;
+            print_plans(&plans, json)?;
+        }
+        DbCommand::Introspect { format } => {
+            let metas = m.fetch_tables(&rb).await?;
+            match format {
+                Format::Json => println!("{}", to_snapshot(&metas)?),
+                Format::Rust => print!("{}", generate(&m, &metas)),
+            }
+        }
+        DbCommand::Status { dir } => {
+            let list = Migration::from_dir(dir)?;
+            for v in rb.migrate_status(&m, &list).await? {
+                let applied_at = v.applied_at.map(|v| v.to_string()).unwrap_or_default();
+                println!(
+                    "{:>8}  {:<32} {:<8?} {}",
+                    v.version, v.name, v.state, applied_at
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn connect(url: &str, command: DbCommand) -> Result<()> {
+    let rb = rbatis::RBatis::new();
+    let scheme = url.split(':').next().unwrap_or_default();
+    match scheme {
+        "sqlite" => {
+            rb.link(SqliteDriver {}, url).await?;
+            run(rb.into(), SqliteMigrator::new(), command).await
+        }
+        "mysql" => {
+            rb.link(MysqlDriver {}, url).await?;
+            run(rb.into(), MysqlMigrator::new(), command).await
+        }
+        "postgres" | "postgresql" => {
+            rb.link(PostgresDriver {}, url).await?;
+            run(rb.into(), PostgresMigrator::new(), command).await
+        }
+        _ => Err(format!("unsupported database url: {}", url).into()),
+    }
+}
+
+async fn execute(cli: Cli) -> Result<bool> {
+    match cli.command {
+        Command::Diff { old, new, json } => {
+            let plans = diff(&read_snapshot(&old)?, &read_snapshot(&new)?)?;
+            print_plans(&plans, json)?;
+            Ok(plans.is_empty())
+        }
+        Command::Db(command) => {
+            let url = cli.url.ok_or("missing --url or DATABASE_URL")?;
+            connect(&url, command).await?;
+            Ok(true)
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    match execute(Cli::parse()).await {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
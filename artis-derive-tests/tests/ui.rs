@@ -0,0 +1,6 @@
+// derive 属性错误时的编译错误，TRYBUILD=overwrite 更新 .stderr
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+#[derive(artis::Artis)]
+pub struct Person {
+    #[artis(PRIMARY, AUTO_INCREMENT)]
+    pub id: Option<u64>,
+    #[artis(size = "32")]
+    pub name: String,
+}
+
+fn main() {}
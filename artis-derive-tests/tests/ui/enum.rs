@@ -0,0 +1,7 @@
+#[derive(artis::Artis)]
+pub enum Person {
+    Jack,
+    Tom,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[derive(artis::Artis)]
+#[artis(table = "persons", hooks)]
+pub struct Person {
+    #[artis(PRIMARY, AUTO_INCREMENT)]
+    pub id: Option<u64>,
+}
+
+fn main() {}
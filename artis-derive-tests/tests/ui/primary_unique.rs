@@ -0,0 +1,8 @@
+#[derive(artis::Artis)]
+pub struct Person {
+    #[artis(PRIMARY, UNIQUE)]
+    pub id: Option<u64>,
+    pub name: String,
+}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[derive(artis::Artis)]
+pub struct Person(u64, String);
+
+fn main() {}
@@ -1,218 +1,674 @@
-use proc_macro2::{Literal, TokenStream, TokenTree};
+use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
-    parse_macro_input, Attribute, DataStruct, DeriveInput, GenericArgument, PathSegment, Type,
+    ext::IdentExt, parse_macro_input, Attribute, DataStruct, DeriveInput, Expr, ExprLit, Fields,
+    GenericArgument, Lit, LitInt, LitStr, Meta, PathSegment, Token, Type,
 };
 
 fn extrat_colume(v: &PathSegment) -> String {
-    if v.arguments.is_empty() {
-        return v.ident.to_string();
-    }
     let raw = v.ident.to_string();
-    match raw.as_str() {
-        "Vec" => return "Vec".into(),
-        "HashMap" => return "Map".into(),
-        _ => {}
+    let syn::PathArguments::AngleBracketed(args) = &v.arguments else {
+        return raw;
     };
-    if let syn::PathArguments::AngleBracketed(v) = &v.arguments {
-        if let GenericArgument::Type(v) = v.args.first().unwrap() {
-            return extrat_type(v);
-        }
+    let Some(GenericArgument::Type(t)) = args.args.first() else {
+        return raw;
+    };
+    match raw.as_str() {
+        "Option" | "Box" | "Arc" | "Rc" => extrat_type(t),
+        "Vec" if extrat_type(t) == "u8" => "Bytes".into(),
+        "Vec" => "Vec".into(),
+        "HashMap" | "BTreeMap" => "Map".into(),
+        _ => raw,
     }
-    "".into()
 }
 
+fn is_option(t: &syn::Type) -> bool {
+    let Type::Path(v) = t else {
+        return false;
+    };
+    v.path.segments.last().is_some_and(|v| v.ident == "Option")
+}
+
+// 取路径最后一段，如 chrono::NaiveDate 对应 NaiveDate
 fn extrat_type(t: &syn::Type) -> String {
     if let Type::Path(v) = t {
-        return extrat_colume(v.path.segments.first().unwrap());
+        if let Some(v) = v.path.segments.last() {
+            return extrat_colume(v);
+        }
     }
-    return "".into();
+    "".into()
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct Artis {
     pub table: String,
     pub name: String,
     pub typ: String,
-    pub size: Option<TokenTree>,
-    pub nonull: bool,
+    pub size: usize,
+    pub nonull: Option<bool>, // 未指定时为空，开启 infer_nullable 时由 Option<T> 推断
     pub index: bool,
     pub unique: bool,
     pub primary: bool,
     pub default: String,
     pub comment: String,
     pub increment: bool,
+    pub rename: String,
+    pub prune: bool,
+    pub as_: String,
+    pub column: String, // 字段名
+    pub skip: bool,
+    pub created_at: bool,
+    pub updated_at: bool,
+    pub soft_delete: bool,
+    pub version: bool,
+    pub relation: Option<(String, LitStr)>, // 关联类型与模型
+    pub foreign_key: String,
+    pub ident: Option<syn::Ident>,
+    pub ty: Option<Type>,
+    pub rename_all: String,
+    pub model: bool, // 生成 IntoTable、Hooks、Model 与字段常量
+    pub hooks: bool, // 自行实现 Hooks
+    pub infer_nullable: bool,
 }
 
-impl Default for Artis {
-    fn default() -> Self {
-        Self {
-            table: "".into(),
-            name: "".into(),
-            typ: "".into(),
-            size: Some(Literal::i32_unsuffixed(0).into()),
-            nonull: false,
-            index: false,
-            unique: false,
-            primary: false,
-            default: "".into(),
-            comment: "".into(),
-            increment: false,
+const TABLE_KEYS: &str = "table, comment, rename_all, model, hooks, infer_nullable, PRUNE";
+const FIELD_KEYS: &str = "type, size, default, comment, as, rename, rename_from, skip, created_at, updated_at, soft_delete, version, has_many, has_one, belongs_to, foreign_key, INDEX, UNIQUE, PRIMARY, AUTO_INCREMENT, NOT_NULL, NULLABLE";
+
+// 与 serde 的 rename_all 规则一致
+const RENAME_RULES: [&str; 8] = [
+    "lowercase",
+    "UPPERCASE",
+    "PascalCase",
+    "camelCase",
+    "snake_case",
+    "SCREAMING_SNAKE_CASE",
+    "kebab-case",
+    "SCREAMING-KEBAB-CASE",
+];
+
+fn rename_case(v: &str, rule: &str) -> String {
+    let pascal = || -> String {
+        v.split('_')
+            .map(|v| {
+                let mut c = v.chars();
+                match c.next() {
+                    Some(f) => f.to_uppercase().chain(c).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect()
+    };
+    match rule {
+        "lowercase" => v.to_lowercase(),
+        "UPPERCASE" => v.to_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let v = pascal();
+            let mut c = v.chars();
+            match c.next() {
+                Some(f) => f.to_lowercase().chain(c).collect(),
+                None => v,
+            }
         }
+        "SCREAMING_SNAKE_CASE" => v.to_uppercase(),
+        "kebab-case" => v.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => v.replace('_', "-").to_uppercase(),
+        _ => v.into(),
     }
 }
 
-fn extrat_literal(v: Option<TokenTree>, trim: bool) -> String {
-    if v.is_none() {
-        return "".into();
+fn parse_rename_all(v: &LitStr) -> syn::Result<String> {
+    let rule = v.value();
+    if !RENAME_RULES.contains(&rule.as_str()) {
+        let msg = format!(
+            "unknown rename rule, expected one of: {}",
+            RENAME_RULES.join(", ")
+        );
+        return Err(syn::Error::new_spanned(v, msg));
     }
-    if trim {
-        v.unwrap().to_string().trim().trim_matches('"').to_string()
-    } else {
-        v.unwrap().to_string().trim().replace("\"", "'")
+    Ok(rule)
+}
+
+// 默认值：数字原样输出，字符串加引号，标识符如 CURRENT_TIMESTAMP 原样输出
+fn extrat_default(v: &Expr) -> syn::Result<String> {
+    match v {
+        Expr::Lit(ExprLit { lit, .. }) => match lit {
+            Lit::Str(v) => Ok(format!("'{}'", v.value().replace('\'', "''"))),
+            Lit::Int(v) => Ok(v.base10_digits().into()),
+            Lit::Float(v) => Ok(v.base10_digits().into()),
+            Lit::Bool(v) => Ok(v.value.to_string()),
+            _ => Err(syn::Error::new_spanned(
+                v,
+                "expected string, number or bool",
+            )),
+        },
+        Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) if matches!(**expr, Expr::Lit(_)) => Ok(format!("-{}", extrat_default(expr)?)),
+        Expr::Path(v) if v.path.get_ident().is_some() => {
+            Ok(v.path.get_ident().unwrap().to_string())
+        }
+        _ => Err(syn::Error::new_spanned(
+            v,
+            "expected string, number, bool or identifier",
+        )),
     }
 }
 
-impl From<TokenStream> for Artis {
-    fn from(value: TokenStream) -> Self {
-        let mut itr = value.into_iter();
-        let mut artis = Artis::default();
-        while let Some(v) = itr.next() {
-            let raw = v.to_string();
-            match raw.as_str() {
-                "table" => {
-                    itr.next();
-                    artis.table = extrat_literal(itr.next(), true);
-                }
-                "type" => {
-                    itr.next();
-                    artis.typ = extrat_literal(itr.next(), true);
-                }
-                "size" => {
-                    itr.next();
-                    artis.size = itr.next(); //ktol extrat_literal(itr.next()).parse::<i32>().unwrap();
-                }
-                "default" => {
-                    itr.next();
-                    artis.default = extrat_literal(itr.next(), false);
-                }
-                "comment" => {
-                    itr.next();
-                    artis.comment = extrat_literal(itr.next(), false);
+impl Artis {
+    fn parse(&mut self, attr: &Attribute, is_table: bool) -> syn::Result<()> {
+        attr.parse_nested_meta(|meta| {
+            let Some(key) = meta.path.get_ident().map(|v| v.to_string()) else {
+                return Err(meta.error("expected identifier"));
+            };
+            let keys = if is_table { TABLE_KEYS } else { FIELD_KEYS };
+            if !keys.split(", ").any(|v| v == key) {
+                let kind = if is_table { "struct" } else { "field" };
+                return Err(meta.error(format!(
+                    "unknown {} attribute `{}`, expected one of: {}",
+                    kind, key, keys
+                )));
+            }
+            match key.as_str() {
+                "table" => self.table = meta.value()?.parse::<LitStr>()?.value(),
+                "type" => self.typ = meta.value()?.parse::<LitStr>()?.value(),
+                "size" => self.size = meta.value()?.parse::<LitInt>()?.base10_parse()?,
+                "default" => self.default = extrat_default(&meta.value()?.parse::<Expr>()?)?,
+                "comment" => self.comment = meta.value()?.parse::<LitStr>()?.value(),
+                "rename_from" => self.rename = meta.value()?.parse::<LitStr>()?.value(),
+                "rename" => self.column = meta.value()?.parse::<LitStr>()?.value(),
+                "rename_all" => self.rename_all = parse_rename_all(&meta.value()?.parse()?)?,
+                "skip" => self.skip = true,
+                "created_at" => self.created_at = true,
+                "updated_at" => self.updated_at = true,
+                "soft_delete" => self.soft_delete = true,
+                "version" => self.version = true,
+                "has_many" | "has_one" | "belongs_to" => {
+                    if self.relation.is_some() {
+                        return Err(meta.error("duplicate relation"));
+                    }
+                    let v = meta.value()?.parse::<LitStr>()?;
+                    v.parse::<Type>()?;
+                    self.relation = Some((key.clone(), v));
                 }
-                "INDEX" => {
-                    artis.index = true;
+                "foreign_key" => self.foreign_key = meta.value()?.parse::<LitStr>()?.value(),
+                "as" => {
+                    let v = meta.value()?.parse::<LitStr>()?;
+                    v.parse::<Type>()?;
+                    self.as_ = v.value();
                 }
-                "UNIQUE" => {
-                    artis.unique = true;
+                "INDEX" => self.index = true,
+                "UNIQUE" => self.unique = true,
+                "PRIMARY" => self.primary = true,
+                "AUTO_INCREMENT" => self.increment = true,
+                "NOT_NULL" | "NULLABLE" => {
+                    let nonull = key == "NOT_NULL";
+                    if self.nonull == Some(!nonull) {
+                        return Err(meta.error("`NOT_NULL` conflicts with `NULLABLE`"));
+                    }
+                    self.nonull = Some(nonull);
                 }
-                "NOT_NULL" => {
-                    artis.nonull = true;
+                "model" => self.model = true,
+                "hooks" => self.hooks = true,
+                "infer_nullable" => self.infer_nullable = true,
+                "PRUNE" => self.prune = true,
+                _ => unreachable!(),
+            }
+            Ok(())
+        })
+    }
+
+    fn check(&self, attr: &Attribute) -> syn::Result<()> {
+        let conflicts = [
+            (self.primary && self.unique, "PRIMARY", "UNIQUE"),
+            (self.primary && self.index, "PRIMARY", "INDEX"),
+            (
+                self.primary && self.nonull == Some(false),
+                "PRIMARY",
+                "NULLABLE",
+            ),
+            (self.unique && self.index, "UNIQUE", "INDEX"),
+            (
+                self.created_at && self.updated_at,
+                "created_at",
+                "updated_at",
+            ),
+            (self.primary && self.created_at, "PRIMARY", "created_at"),
+            (self.primary && self.updated_at, "PRIMARY", "updated_at"),
+            (self.primary && self.soft_delete, "PRIMARY", "soft_delete"),
+            (
+                self.soft_delete && self.nonull == Some(true),
+                "soft_delete",
+                "NOT_NULL",
+            ),
+            (
+                self.soft_delete && self.created_at,
+                "soft_delete",
+                "created_at",
+            ),
+            (
+                self.soft_delete && self.updated_at,
+                "soft_delete",
+                "updated_at",
+            ),
+        ];
+        for (v, a, b) in conflicts {
+            if v {
+                let msg = format!("`{}` conflicts with `{}`", a, b);
+                return Err(syn::Error::new_spanned(attr, msg));
+            }
+        }
+        Ok(())
+    }
+}
+
+// 读取 #[serde(...)] 中的 rename、rename_all 与 skip，忽略其他属性
+fn extrat_serde(list: &[Attribute], artis: &mut Artis) -> syn::Result<()> {
+    for v in list.iter().filter(|v| v.path().is_ident("serde")) {
+        v.parse_nested_meta(|meta| {
+            let key = meta.path.get_ident().map(|v| v.to_string());
+            match key.as_deref() {
+                Some("skip" | "skip_serializing") => artis.skip = true,
+                Some("rename" | "rename_all") if meta.input.peek(Token![=]) => {
+                    let v: LitStr = meta.value()?.parse()?;
+                    if key.as_deref() == Some("rename") {
+                        artis.column = v.value();
+                    } else {
+                        artis.rename_all = parse_rename_all(&v)?;
+                    }
                 }
-                "PRIMARY" => {
-                    artis.primary = true;
-                    artis.nonull = true;
+                // rename(serialize = "..")
+                Some("rename" | "rename_all") => meta.parse_nested_meta(|inner| {
+                    let v: LitStr = inner.value()?.parse()?;
+                    if !inner.path.is_ident("serialize") {
+                        return Ok(());
+                    }
+                    if key.as_deref() == Some("rename") {
+                        artis.column = v.value();
+                    } else {
+                        artis.rename_all = parse_rename_all(&v)?;
+                    }
+                    Ok(())
+                })?,
+                _ if meta.input.peek(Token![=]) => {
+                    meta.value()?.parse::<Expr>()?;
                 }
-                "AUTO_INCREMENT" => {
-                    artis.increment = true;
+                _ if meta.input.peek(syn::token::Paren) => {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    content.parse::<TokenStream>()?;
                 }
                 _ => {}
             }
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// 模型按 serde 序列化写入，artis 的 rename、rename_all、skip 须有相同的 serde 属性
+fn check_serde(serde: &Artis, artis: &Artis, attr: &Attribute) -> syn::Result<()> {
+    let mismatches = [
+        (
+            artis.column != serde.column,
+            format!("rename = \"{}\"", artis.column),
+        ),
+        (
+            artis.rename_all != serde.rename_all,
+            format!("rename_all = \"{}\"", artis.rename_all),
+        ),
+        (artis.skip && !serde.skip, "skip".into()),
+    ];
+    for (v, key) in mismatches {
+        if v {
+            let msg = format!("`{}` requires a matching `#[serde({})]`", key, key);
+            return Err(syn::Error::new_spanned(attr, msg));
         }
-        artis
     }
+    Ok(())
 }
 
-fn extrat_attrs(list: Vec<Attribute>) -> Option<Artis> {
+fn extrat_attrs(list: &[Attribute], is_table: bool) -> syn::Result<Artis> {
+    let mut artis = Artis::default();
+    extrat_serde(list, &mut artis)?;
+    let serde = artis.clone();
+    for v in list.iter().filter(|v| v.path().is_ident("artis")) {
+        artis.parse(v, is_table)?;
+        artis.check(v)?;
+        check_serde(&serde, &artis, v)?;
+    }
+    Ok(artis)
+}
+
+// 文档注释 `///` 作为默认注释
+fn extrat_doc(list: &[Attribute]) -> String {
+    let mut lines: Vec<String> = vec![];
     for v in list {
-        let meta = &v.meta.require_list().unwrap();
-        if !meta.path.is_ident("artis") {
+        let Meta::NameValue(meta) = &v.meta else {
+            continue;
+        };
+        if !meta.path.is_ident("doc") {
             continue;
         }
-        return Some(meta.tokens.clone().into());
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Str(v), ..
+        }) = &meta.value
+        {
+            let line = v.value().trim().to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
     }
-    None
+    lines.join(" ")
 }
 
-fn extend_feilds(v: &DataStruct) -> Vec<Artis> {
+fn extend_feilds(v: &DataStruct, table: &Artis) -> syn::Result<Vec<Artis>> {
+    let rename_all = table.rename_all.as_str();
     let mut fields: Vec<Artis> = vec![];
     for field in &v.fields {
-        let mut artis = Artis::default();
-        if let Some(v) = extrat_attrs(field.attrs.clone()) {
-            artis = v;
+        let mut artis = extrat_attrs(&field.attrs, false)?;
+        if artis.comment.is_empty() {
+            artis.comment = extrat_doc(&field.attrs);
+        }
+        artis.name = field.ident.as_ref().unwrap().unraw().to_string();
+        artis.ident = field.ident.clone();
+        artis.ty = Some(field.ty.clone());
+        if !artis.column.is_empty() {
+            artis.name = artis.column.clone();
+        } else if !rename_all.is_empty() {
+            artis.name = rename_case(&artis.name, rename_all);
+        }
+        if artis.relation.is_some() == artis.foreign_key.is_empty() {
+            let msg = "relation requires both `foreign_key` and one of `has_many`, `has_one`, `belongs_to`";
+            return Err(syn::Error::new_spanned(&field.ident, msg));
+        }
+        if artis.relation.is_some() {
+            // 关联字段不生成表字段，可使用 #[serde(skip_serializing)]
+            fields.push(artis);
+            continue;
+        }
+        if artis.skip {
+            continue;
+        }
+        if artis.primary {
+            artis.nonull = Some(true);
+        }
+        if artis.soft_delete {
+            // 未删除的记录为空值
+            artis.nonull = Some(false);
+        }
+        // 推断会修改已有表的字段约束，需显式开启
+        if artis.nonull.is_none() && table.infer_nullable {
+            artis.nonull = Some(!is_option(&field.ty));
+        }
+        // 时间戳与软删除字段默认映射为 DateTime，时间戳由数据库填充默认值
+        let is_stamp = artis.created_at || artis.updated_at;
+        if (is_stamp || artis.soft_delete) && artis.typ.is_empty() && artis.as_.is_empty() {
+            artis.typ = ":DateTime".into();
+        }
+        if is_stamp && artis.default.is_empty() {
+            artis.default = "CURRENT_TIMESTAMP".into();
+        }
+        if artis.version {
+            // 版本号需要参与比较与递增，不能为空
+            if is_option(&field.ty) {
+                let msg = "`version` requires a non-optional integer field";
+                return Err(syn::Error::new_spanned(&field.ty, msg));
+            }
+            if artis.default.is_empty() {
+                artis.default = "0".into();
+            }
+        }
+        if artis.typ.is_empty() && !artis.as_.is_empty() {
+            // 按指定的 Rust 类型映射字段类型
+            let t: Type = syn::parse_str(&artis.as_)?;
+            artis.typ = format!(":{}", extrat_type(&t));
         }
-        artis.name = field.ident.as_ref().unwrap().to_string();
         if artis.typ.is_empty() {
             artis.typ = format!(":{}", extrat_type(&field.ty));
         }
         fields.push(artis);
     }
-    fields
+    Ok(fields)
 }
 
-#[proc_macro_derive(Artis, attributes(artis))]
-pub fn device_artis(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input: DeriveInput = parse_macro_input!(input);
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
     let name = input.ident;
-    let mut table = format!("{}s", name.to_string().to_lowercase());
-    if let Some(v) = extrat_attrs(input.attrs) {
-        if !v.table.is_empty() {
-            table = v.table
+    let s = match input.data {
+        syn::Data::Struct(s) if matches!(s.fields, Fields::Named(_)) => s,
+        syn::Data::Struct(_) => {
+            let msg = "Artis can only be derived for structs with named fields";
+            return Err(syn::Error::new_spanned(name, msg));
+        }
+        syn::Data::Enum(v) => {
+            let msg = "Artis cannot be derived for enums";
+            return Err(syn::Error::new_spanned(v.enum_token, msg));
         }
+        syn::Data::Union(v) => {
+            let msg = "Artis cannot be derived for unions";
+            return Err(syn::Error::new_spanned(v.union_token, msg));
+        }
+    };
+    let attrs = extrat_attrs(&input.attrs, true)?;
+    let mut table = format!("{}s", name.to_string().to_lowercase());
+    if !attrs.table.is_empty() {
+        table = attrs.table.clone();
+    }
+    let prune = attrs.prune;
+    let mut comment = attrs.comment.clone();
+    if comment.is_empty() {
+        comment = extrat_doc(&input.attrs);
     }
 
     let mut inx_quote: Vec<TokenStream> = vec![];
     let mut com_quote: Vec<TokenStream> = vec![];
+    let mut col_fields: Vec<TokenStream> = vec![];
+    let mut col_values: Vec<TokenStream> = vec![];
     let mut primary = String::new();
-    if let syn::Data::Struct(s) = input.data {
-        let fields = extend_feilds(&s);
-        for field in fields {
-            let name = &field.name;
-            let colume = field.typ;
-            let size = field.size;
-            let nullable = !field.nonull;
-            let default = field.default;
-            let comment = field.comment;
-            let increment = field.increment;
-            let quote = quote! {artis::migrator::ColumeMeta {
-                name:#name.into(),
-                colume: #colume.into(),
-                size: #size,
-                nullable: #nullable,
-                default: #default.into(),
-                comment: #comment.into(),
-                increment:#increment
-            }};
-            com_quote.push(quote.into());
-
-            if field.primary {
-                primary = field.name;
-                continue;
+    let mut markers: Vec<TokenStream> = vec![];
+    let (mut created_at, mut updated_at) = (false, false);
+    let (mut soft_delete, mut version) = (false, false);
+    let mut relations: Vec<String> = vec![];
+    let mut loads: Vec<TokenStream> = vec![];
+    if attrs.hooks && !attrs.model {
+        let msg = "`hooks` requires `#[artis(model)]`";
+        return Err(syn::Error::new_spanned(&name, msg));
+    }
+    for field in extend_feilds(&s, &attrs)? {
+        if let Some((kind, model)) = &field.relation {
+            if !attrs.model {
+                let msg = format!("`{}` requires `#[artis(model)]`", kind);
+                return Err(syn::Error::new_spanned(&field.ident, msg));
             }
-            if field.unique {
-                inx_quote.push(quote! {
-                    artis::migrator::IndexMeta::Unique(#name.into())
-                });
+            let (ident, column) = (&field.ident, &field.name);
+            let kind = syn::Ident::new(kind, model.span());
+            let model: Type = model.parse()?;
+            let foreign_key = &field.foreign_key;
+            loads.push(quote! {
+                #column => artis::relation::#kind::<Self, #model>(
+                    rb, list, #foreign_key, |v, rows| v.#ident = rows,
+                ).await
+            });
+            relations.push(field.name);
+            continue;
+        }
+        for (marked, seen, key) in [
+            (field.created_at, &mut created_at, "created_at"),
+            (field.updated_at, &mut updated_at, "updated_at"),
+            (field.soft_delete, &mut soft_delete, "soft_delete"),
+            (field.version, &mut version, "version"),
+        ] {
+            if !marked {
                 continue;
             }
-            if field.index {
-                inx_quote.push(quote! {
-                    artis::migrator::IndexMeta::Index(#name.into())
+            if *seen {
+                let msg = format!("duplicate `{}` field", key);
+                return Err(syn::Error::new_spanned(&field.ident, msg));
+            }
+            *seen = true;
+            let (method, column) = (syn::Ident::new(key, name.span()), &field.name);
+            markers.push(quote! {
+                fn #method() -> Option<&'static str> {
+                    Some(#column)
+                }
+            });
+        }
+        if field.version {
+            let ident = &field.ident;
+            markers.push(quote! {
+                fn increase_version(&mut self) {
+                    self.#ident += 1;
+                }
+            });
+        }
+
+        let (ident, ty) = (&field.ident, &field.ty);
+        let column = &field.name;
+        col_fields.push(quote! { pub #ident: artis::Column<#name, #ty> });
+        col_values.push(quote! { #ident: artis::Column::new(#column) });
+
+        let name = &field.name;
+        let colume = field.typ;
+        let size = field.size;
+        let nullable = field.nonull != Some(true);
+        let default = field.default;
+        let comment = field.comment;
+        let increment = field.increment;
+        let rename = field.rename;
+        let quote = quote! {artis::migrator::ColumeMeta {
+            name:#name.into(),
+            colume: #colume.into(),
+            size: #size,
+            nullable: #nullable,
+            default: #default.into(),
+            comment: #comment.into(),
+            increment:#increment,
+            rename: #rename.into()
+        }};
+        com_quote.push(quote);
+
+        if field.primary {
+            if field.increment {
+                markers.push(quote! {
+                    fn set_increment(&mut self, id: artis::Value) -> artis::Result<()> {
+                        self.#ident = artis::from_value(id)?;
+                        Ok(())
+                    }
                 });
             }
+            primary = field.name;
+            continue;
+        }
+        if field.unique {
+            inx_quote.push(quote! {
+                artis::migrator::IndexMeta::Unique(#name.into())
+            });
+            continue;
+        }
+        if field.index {
+            inx_quote.push(quote! {
+                artis::migrator::IndexMeta::Index(#name.into())
+            });
         }
     }
-    quote! {
+    // 没有关联时使用默认实现
+    let relation = if loads.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn relations() -> &'static [&'static str] {
+                &[#(#relations,)*]
+            }
+
+            fn load(
+                rb: &impl artis::IntoArtis,
+                name: &str,
+                list: &mut [Self],
+            ) -> impl std::future::Future<Output = artis::Result<()>> {
+                let name = name.to_string();
+                async move {
+                    match name.as_str() {
+                        #(#loads,)*
+                        _ => Err(format!("{} has no relation {}", #table, name).into()),
+                    }
+                }
+            }
+        }
+    };
+    let hooks = if attrs.hooks {
+        quote! {}
+    } else {
+        quote! { impl artis::Hooks for #name {} }
+    };
+    let vis = &input.vis;
+    let columns = syn::Ident::new(&format!("{}Columns", name), name.span());
+    let doc = format!("Columns of [`{}`].", name);
+    // 标记 #[artis(model)] 时才生成，避免与自行实现的 IntoTable 冲突
+    let model = if !attrs.model {
+        quote! {}
+    } else {
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy)]
+            #vis struct #columns {
+                #(#col_fields,)*
+            }
+
+            impl #name {
+                pub const COLUMNS: #columns = #columns {
+                    #(#col_values,)*
+                };
+            }
+
+            impl artis::IntoTable for #name {
+                fn into_table(&self) -> String {
+                    #table.into()
+                }
+            }
+
+            #hooks
+
+            impl artis::Model for #name {
+                fn table() -> &'static str {
+                    #table
+                }
+
+                fn primary() -> &'static str {
+                    #primary
+                }
+
+                #(#markers)*
+
+                #relation
+            }
+        }
+    };
+    Ok(quote! {
+        #model
+
         impl artis::migrator::ArtisMigrator for #name {
             fn migrator() -> artis::migrator::TableMeta {
                 artis::migrator::TableMeta {
                     name: #table.into(),
+                    comment: #comment.into(),
                     primary: #primary.into(),
                     columes: vec![#(#com_quote,)*],
-                    indexs: vec![#(#inx_quote,)*]
+                    indexs: vec![#(#inx_quote,)*],
+                    prune: #prune,
+                    ..Default::default()
                 }
             }
         }
-    }
-    .into()
+    })
+}
+
+#[proc_macro_derive(Artis, attributes(artis))]
+pub fn device_artis(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
@@ -4,7 +4,7 @@ use std::{fmt::Debug, sync::Arc};
 
 use serde::de::DeserializeOwned;
 
-use crate::decode::{decode, decode_pluck};
+use crate::decode::{decode, decode_pluck, merge};
 use crate::types::{Args, ExecResult, IntoArtis, IntoChunk, RawType};
 use crate::ArtisTx;
 use crate::IntoRaw;
@@ -42,6 +42,41 @@ impl Artis {
         let rb = Arc::new(self.c.begin().await?);
         Ok(rb.chunk(func(Arc::clone(&rb))).await?)
     }
+
+    // 拆分后的查询在同一事务中依次执行并合并结果
+    async fn fetch_raw(&self, i: &dyn IntoRaw, t: RawType) -> Result<Value> {
+        let mut list = i.split_raw(t)?;
+        if list.len() == 1 {
+            let (raw, args) = list.remove(0);
+            return Ok(self.c.query(raw, args).await?);
+        }
+        let tx = self.begin().await?;
+        tx.chunk(async {
+            let mut rows = vec![];
+            for v in list {
+                rows.push(tx.query(&v).await?);
+            }
+            Ok(merge(rows))
+        })
+        .await
+    }
+
+    async fn exec_raw(&self, i: &dyn IntoRaw, t: RawType) -> Result<u64> {
+        let mut list = i.split_raw(t)?;
+        if list.len() == 1 {
+            let (raw, args) = list.remove(0);
+            return Ok(self.c.exec(raw, args).await?.rows_affected);
+        }
+        let tx = self.begin().await?;
+        tx.chunk(async {
+            let mut rows_affected = 0;
+            for (raw, args) in list {
+                rows_affected += tx.exec(&raw, args).await?.rows_affected;
+            }
+            Ok(rows_affected)
+        })
+        .await
+    }
 }
 
 impl IntoChunk for Artis {
@@ -56,13 +91,14 @@ impl IntoChunk for Artis {
 
 impl IntoArtis for Artis {
     async fn fetch<T: DeserializeOwned>(&self, i: &dyn IntoRaw) -> Result<T> {
-        let (raw, args) = i.into_raw(RawType::Fetch);
-        Ok(decode(self.c.query(raw, args).await?)?)
+        Ok(decode(self.fetch_raw(i, RawType::Fetch).await?)?)
     }
 
     async fn pluck<T: DeserializeOwned>(&self, i: &dyn IntoRaw, colume: &'static str) -> Result<T> {
-        let (raw, args) = i.into_raw(RawType::Fetch);
-        Ok(decode_pluck(self.c.query(raw, args).await?, colume)?)
+        Ok(decode_pluck(
+            self.fetch_raw(i, RawType::Fetch).await?,
+            colume,
+        )?)
     }
 
     async fn saving(&self, i: &dyn IntoRaw) -> Result<Value> {
@@ -71,13 +107,11 @@ impl IntoArtis for Artis {
     }
 
     async fn update(&self, i: &dyn IntoRaw) -> Result<u64> {
-        let (raw, args) = i.into_raw(RawType::Update);
-        Ok(self.c.exec(raw, args).await?.rows_affected)
+        self.exec_raw(i, RawType::Update).await
     }
 
     async fn delete(&self, i: &dyn IntoRaw) -> Result<u64> {
-        let (raw, args) = i.into_raw(RawType::Delete);
-        Ok(self.c.exec(raw, args).await?.rows_affected)
+        self.exec_raw(i, RawType::Delete).await
     }
 
     async fn query(&self, i: &dyn IntoRaw) -> Result<Value> {
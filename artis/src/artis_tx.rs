@@ -3,7 +3,7 @@ use std::{fmt::Debug, future::Future, sync::Arc};
 use serde::de::DeserializeOwned;
 
 use crate::{
-    decode::{decode, decode_pluck},
+    decode::{decode, decode_pluck, merge},
     types::{Args, IntoChunk},
     BoxFuture, ExecResult, IntoArtis, IntoRaw, RawType, Result, Value,
 };
@@ -43,6 +43,23 @@ impl ArtisTx {
             }
         }
     }
+
+    // 依次执行拆分后的查询并合并结果，已在事务中
+    async fn fetch_raw(&self, i: &dyn IntoRaw, t: RawType) -> Result<Value> {
+        let mut list = vec![];
+        for (raw, args) in i.split_raw(t)? {
+            list.push(self.c.query(raw, args).await?);
+        }
+        Ok(merge(list))
+    }
+
+    async fn exec_raw(&self, i: &dyn IntoRaw, t: RawType) -> Result<u64> {
+        let mut rows_affected = 0;
+        for (raw, args) in i.split_raw(t)? {
+            rows_affected += self.c.exec(raw, args).await?.rows_affected;
+        }
+        Ok(rows_affected)
+    }
 }
 
 impl IntoChunk for ArtisTx {
@@ -57,13 +74,14 @@ impl IntoChunk for ArtisTx {
 
 impl IntoArtis for ArtisTx {
     async fn fetch<T: DeserializeOwned>(&self, i: &dyn IntoRaw) -> Result<T> {
-        let (raw, args) = i.into_raw(RawType::Fetch);
-        Ok(decode(self.c.query(raw, args).await?)?)
+        Ok(decode(self.fetch_raw(i, RawType::Fetch).await?)?)
     }
 
     async fn pluck<T: DeserializeOwned>(&self, i: &dyn IntoRaw, colume: &'static str) -> Result<T> {
-        let (raw, args) = i.into_raw(RawType::Fetch);
-        Ok(decode_pluck(self.c.query(raw, args).await?, colume)?)
+        Ok(decode_pluck(
+            self.fetch_raw(i, RawType::Fetch).await?,
+            colume,
+        )?)
     }
 
     async fn saving(&self, i: &dyn IntoRaw) -> Result<Value> {
@@ -72,13 +90,11 @@ impl IntoArtis for ArtisTx {
     }
 
     async fn update(&self, i: &dyn IntoRaw) -> Result<u64> {
-        let (raw, args) = i.into_raw(RawType::Update);
-        Ok(self.c.exec(raw, args).await?.rows_affected)
+        self.exec_raw(i, RawType::Update).await
     }
 
     async fn delete(&self, i: &dyn IntoRaw) -> Result<u64> {
-        let (raw, args) = i.into_raw(RawType::Delete);
-        Ok(self.c.exec(raw, args).await?.rows_affected)
+        self.exec_raw(i, RawType::Delete).await
     }
 
     async fn query(&self, i: &dyn IntoRaw) -> Result<Value> {
@@ -2,6 +2,20 @@ use serde::de::DeserializeOwned;
 
 use crate::Value;
 
+// 合并拆分查询的结果
+pub fn merge(mut list: Vec<Value>) -> Value {
+    if list.len() == 1 {
+        return list.remove(0);
+    }
+    let mut rows: Vec<Value> = vec![];
+    for v in list {
+        if let Value::Array(v) = v {
+            rows.extend(v);
+        }
+    }
+    Value::Array(rows)
+}
+
 pub fn decode<T: DeserializeOwned>(v: Value) -> crate::Result<T> {
     let type_name = std::any::type_name::<T>();
     if type_name == std::any::type_name::<u64>() {
@@ -23,7 +37,9 @@ pub fn decode_i64<T: DeserializeOwned>(v: Value) -> crate::Result<T> {
             Err(e) => Err(e.to_string().into()),
         };
     }
-    match rbs::from_value_ref(&v[0]) {
+    // 取第一行第一列，如 COUNT(*) AS total
+    let v = v.as_map().unwrap().0.values().next().unwrap();
+    match rbs::from_value_ref(v) {
         Ok(v) => Ok(v),
         Err(e) => Err(e.to_string().into()),
     }
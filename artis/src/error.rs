@@ -3,6 +3,8 @@ use std::fmt::{self, Display, Formatter};
 #[derive(Debug)]
 pub enum Error {
     E(String),
+    // 乐观锁版本不一致，记录已被其他操作修改或删除
+    StaleObject(String),
 }
 
 impl From<&'static str> for Error {
@@ -27,6 +29,7 @@ impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Error::E(v) => write!(f, "{}", v),
+            Error::StaleObject(v) => write!(f, "stale object: {}", v),
         }
     }
 }
@@ -1,16 +1,44 @@
 use std::fmt::Display;
 
 use rbs::value::map::ValueMap;
+use serde::Serialize;
 
 use crate::{
     raw, rbv,
     types::{Args, Columns, RawType},
-    Value,
+    Column, Value,
 };
 
 pub trait IntoRaw: Sync {
     fn into_raw(&self, v: RawType) -> (String, Vec<crate::Value>);
-}
+
+    // 参数过多时拆分为多条语句，在同一事务中执行，查询结果合并，影响行数累加
+    fn split_raw(&self, v: RawType) -> crate::Result<Vec<(String, Vec<crate::Value>)>> {
+        Ok(vec![self.into_raw(v)])
+    }
+}
+
+// Raw.inner 中各子句的位置
+const MODEL: usize = 0;
+const SELECT: usize = 1;
+const WHERE: usize = 2;
+const GROUP: usize = 3;
+const ORDER: usize = 4;
+const LIMIT: usize = 5;
+const TRASHED: usize = 6;
+const IN: usize = 7;
+const HAVING: usize = 8;
+const DISTINCT: usize = 9;
+const AGGREGATE: usize = 10;
+const SUB: usize = 11;
+const SELECT_SUB: usize = 12;
+const COMPOUND: usize = 13;
+const WITH: usize = 14;
+const INCREASE: usize = 15;
+const PROPS: usize = 16;
+
+// 单条语句的参数上限，sqlite 为 32766，postgres 与 mysql 为 65535
+const MAX_ARGS: usize = 30000;
 
 pub trait IntoTable: Sync {
     fn into_table(&self) -> String;
@@ -28,6 +56,22 @@ impl IntoTable for &'static str {
     }
 }
 
+pub trait IntoColumn {
+    fn column(&self) -> String;
+}
+
+impl IntoColumn for &str {
+    fn column(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoColumn for String {
+    fn column(&self) -> String {
+        self.to_owned()
+    }
+}
+
 pub trait IntoLimit: Clone + Sync {
     fn into_limit(&self) -> (u32, u32);
 }
@@ -48,60 +92,265 @@ impl IntoLimit for (i32, i32) {
 enum Props {
     Empty,
     Model(crate::Value),
+    Values(crate::Value), // 模型字段，数组同样作为参数绑定
     Limit((u32, u32)),
     Where((String, Vec<crate::Value>)),
     Group(String),
     Order(String),
     Select(Vec<String>),
+    Trashed((String, Trashed)),
+    In(Vec<(String, bool, Vec<crate::Value>)>), // [(column,not,args)]
+    Having((String, Vec<crate::Value>)),
+    Distinct,
+    Aggregate(Vec<String>),
+    Sub(Vec<(String, Raw)>),          // [(条件或别名,子查询)]
+    Compound(Vec<(String, Raw)>),     // [(UNION 等,查询)]
+    With((bool, Vec<(String, Raw)>)), // (recursive,[(名称,查询)])
+    Increase(Vec<String>),
+}
+
+// 软删除的查询范围
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Trashed {
+    Without,
+    With,
+    Only,
 }
 
 #[derive(Debug, Clone)]
 pub struct Raw {
     table: String,
-    inner: Vec<Props>, // [model,select,where,order,limit]
+    inner: Vec<Props>, // 各子句按 MODEL、SELECT 等常量的位置存放
 }
 
 impl Raw {
     pub fn table(v: &str) -> Self {
         Self {
             table: v.into(),
-            inner: vec![Props::Empty; 6],
+            inner: vec![Props::Empty; PROPS],
         }
     }
 
     pub fn model(mut self, v: crate::Value) -> Self {
-        self.inner[0] = Props::Model(v);
+        self.inner[MODEL] = Props::Model(v);
+        self
+    }
+
+    // 模型增删改查使用，字段值全部绑定为参数，不解析运算符
+    pub(crate) fn values(mut self, v: crate::Value) -> Self {
+        self.inner[MODEL] = Props::Values(v);
+        self
+    }
+
+    // 更新时字段加一，如版本号
+    pub(crate) fn increase(mut self, column: &str) -> Self {
+        if let Props::Increase(list) = &mut self.inner[INCREASE] {
+            list.push(column.into());
+        } else {
+            self.inner[INCREASE] = Props::Increase(vec![column.into()]);
+        }
         self
     }
 
     pub fn select(mut self, v: Vec<&str>) -> Self {
-        self.inner[1] = Props::Select(v.iter().map(|v| v.to_string()).collect());
+        self.inner[SELECT] = Props::Select(v.iter().map(|v| v.to_string()).collect());
+        self
+    }
+
+    // 字段常量与字符串可混用，如 &[&c.id, &c.name, &"COUNT(*)"]
+    pub fn select_columns(mut self, v: &[&dyn IntoColumn]) -> Self {
+        self.inner[SELECT] = Props::Select(v.iter().map(|v| v.column()).collect());
         self
     }
 
     pub fn where_(mut self, v: &str, args: Vec<crate::Value>) -> Self {
-        self.inner[2] = Props::Where((v.into(), args));
+        self.inner[WHERE] = Props::Where((v.into(), args));
         self
     }
 
-    pub fn group(mut self, v: &str) -> Self {
-        self.inner[3] = Props::Group(v.into());
+    // 参数类型与字段类型一致，如 where_column(c.age, ">", 18)
+    pub fn where_column<M, T: Serialize>(self, column: Column<M, T>, op: &str, v: T) -> Self {
+        let args = vec![rbs::value(v).unwrap_or_default()];
+        self.where_(&raw!("{} {} ?", column, op), args)
+    }
+
+    pub fn group(mut self, v: impl IntoColumn) -> Self {
+        self.inner[GROUP] = Props::Group(v.column());
         self
     }
 
-    pub fn order(mut self, v: &str) -> Self {
-        self.inner[4] = Props::Order(v.into());
+    pub fn order(mut self, v: impl IntoColumn) -> Self {
+        self.inner[ORDER] = Props::Order(v.column());
         self
     }
 
+    pub fn order_asc<M, T>(self, column: Column<M, T>) -> Self {
+        self.order(column.asc())
+    }
+
+    pub fn order_desc<M, T>(self, column: Column<M, T>) -> Self {
+        self.order(column.desc())
+    }
+
     pub fn limit(mut self, v: impl IntoLimit) -> Self {
-        self.inner[5] = Props::Limit(v.into_limit());
+        self.inner[LIMIT] = Props::Limit(v.into_limit());
+        self
+    }
+
+    pub fn having(mut self, v: &str, args: Vec<crate::Value>) -> Self {
+        self.inner[HAVING] = Props::Having((v.into(), args));
+        self
+    }
+
+    pub fn distinct(mut self) -> Self {
+        self.inner[DISTINCT] = Props::Distinct;
+        self
+    }
+
+    // 聚合字段追加在 select 字段之后，alias 为空时不生成别名
+    pub fn count(self, column: impl IntoColumn, alias: &str) -> Self {
+        self.aggregate("COUNT", column.column(), alias)
+    }
+
+    pub fn sum(self, column: impl IntoColumn, alias: &str) -> Self {
+        self.aggregate("SUM", column.column(), alias)
+    }
+
+    pub fn avg(self, column: impl IntoColumn, alias: &str) -> Self {
+        self.aggregate("AVG", column.column(), alias)
+    }
+
+    pub fn min(self, column: impl IntoColumn, alias: &str) -> Self {
+        self.aggregate("MIN", column.column(), alias)
+    }
+
+    pub fn max(self, column: impl IntoColumn, alias: &str) -> Self {
+        self.aggregate("MAX", column.column(), alias)
+    }
+
+    fn aggregate(mut self, func: &str, column: String, alias: &str) -> Self {
+        let mut v = raw!("{}({})", func, column);
+        if !alias.is_empty() {
+            v.push_str(&raw!(" AS {}", alias));
+        }
+        if let Props::Aggregate(list) = &mut self.inner[AGGREGATE] {
+            list.push(v);
+        } else {
+            self.inner[AGGREGATE] = Props::Aggregate(vec![v]);
+        }
+        self
+    }
+
+    // 子查询条件，如 where_sub("id IN", raw) 生成 id IN (SELECT ...)
+    pub fn where_sub(self, v: &str, raw: Raw) -> Self {
+        self.push_sub(SUB, v.into(), raw)
+    }
+
+    pub fn exists(self, raw: Raw) -> Self {
+        self.push_sub(SUB, "EXISTS".into(), raw)
+    }
+
+    pub fn not_exists(self, raw: Raw) -> Self {
+        self.push_sub(SUB, "NOT EXISTS".into(), raw)
+    }
+
+    // 子查询字段，追加在聚合字段之后
+    pub fn select_sub(self, raw: Raw, alias: &str) -> Self {
+        self.push_sub(SELECT_SUB, alias.into(), raw)
+    }
+
+    // 组合查询，ORDER BY 与 LIMIT 作用于组合后的结果
+    pub fn union(self, raw: Raw) -> Self {
+        self.push_sub(COMPOUND, "UNION".into(), raw)
+    }
+
+    pub fn union_all(self, raw: Raw) -> Self {
+        self.push_sub(COMPOUND, "UNION ALL".into(), raw)
+    }
+
+    pub fn intersect(self, raw: Raw) -> Self {
+        self.push_sub(COMPOUND, "INTERSECT".into(), raw)
+    }
+
+    pub fn except(self, raw: Raw) -> Self {
+        self.push_sub(COMPOUND, "EXCEPT".into(), raw)
+    }
+
+    fn push_sub(mut self, i: usize, v: String, raw: Raw) -> Self {
+        match &mut self.inner[i] {
+            Props::Sub(list) | Props::Compound(list) => list.push((v, raw)),
+            _ if i == COMPOUND => self.inner[i] = Props::Compound(vec![(v, raw)]),
+            _ => self.inner[i] = Props::Sub(vec![(v, raw)]),
+        }
+        self
+    }
+
+    // 公用表表达式，name 可带字段列表，如 tree(id, parent_id)
+    pub fn with(self, name: &str, raw: Raw) -> Self {
+        self.push_with(false, name.into(), raw)
+    }
+
+    // 递归查询通常由 union_all 组合初始查询与递归部分
+    pub fn with_recursive(self, name: &str, raw: Raw) -> Self {
+        self.push_with(true, name.into(), raw)
+    }
+
+    fn push_with(mut self, recursive: bool, name: String, raw: Raw) -> Self {
+        if let Props::With((r, list)) = &mut self.inner[WITH] {
+            *r |= recursive;
+            list.push((name, raw));
+        } else {
+            self.inner[WITH] = Props::With((recursive, vec![(name, raw)]));
+        }
+        self
+    }
+
+    // 空列表时条件恒为假
+    pub fn where_in(self, column: impl IntoColumn, args: Vec<crate::Value>) -> Self {
+        self.push_in(column.column(), false, args)
+    }
+
+    // 空列表时条件恒为真
+    pub fn where_not_in(self, column: impl IntoColumn, args: Vec<crate::Value>) -> Self {
+        self.push_in(column.column(), true, args)
+    }
+
+    fn push_in(mut self, column: String, not: bool, args: Vec<crate::Value>) -> Self {
+        if let Props::In(list) = &mut self.inner[IN] {
+            list.push((column, not, args));
+        } else {
+            self.inner[IN] = Props::In(vec![(column, not, args)]);
+        }
+        self
+    }
+
+    // 软删除字段，默认排除已删除的记录
+    pub fn soft_delete(mut self, column: &str) -> Self {
+        self.inner[TRASHED] = Props::Trashed((column.into(), Trashed::Without));
+        self
+    }
+
+    // 包含已删除的记录
+    pub fn with_trashed(self) -> Self {
+        self.trashed(Trashed::With)
+    }
+
+    // 只查询已删除的记录
+    pub fn only_trashed(self) -> Self {
+        self.trashed(Trashed::Only)
+    }
+
+    fn trashed(mut self, v: Trashed) -> Self {
+        if let Props::Trashed((_, t)) = &mut self.inner[TRASHED] {
+            *t = v;
+        }
         self
     }
 }
 
 impl Raw {
-    fn extend_map(t: RawType, v: &Value, args: &mut Args, s: &Columns) -> Columns {
+    // plain 为 true 时数组作为参数绑定，否则视为运算符，如 ["= age + ?", 1]
+    fn extend_map(t: RawType, v: &Value, args: &mut Args, s: &Columns, plain: bool) -> Columns {
         if !v.is_map() {
             return vec![];
         }
@@ -124,7 +373,7 @@ impl Raw {
             if t.is_saving() {
                 columns.push(k.to_owned());
                 args.push(value);
-            } else if let Value::Array(list) = value {
+            } else if let (Value::Array(list), false) = (&value, plain) {
                 if list.len() == 0 || list.len() > 2 || !list[0].is_str() {
                     return;
                 }
@@ -140,104 +389,279 @@ impl Raw {
         columns
     }
 
-    fn into_fetch(raw: &mut String, args: &mut Vec<Value>, t: &str, s: &Columns, v: &Value) {
-        let mut columns = s.join(", ");
-        if s.is_empty() {
-            columns = "*".into();
+    // c 为查询字段，s 为 select 指定的字段
+    fn into_fetch(
+        raw: &mut String,
+        args: &mut Args,
+        t: &str,
+        c: &Columns,
+        s: &Columns,
+        v: &Value,
+        plain: bool,
+    ) -> Columns {
+        raw.push_str(&raw!("SELECT {} FROM {}", c.join(", "), t));
+        Raw::extend_map(RawType::Fetch, v, args, s, plain)
+    }
+
+    // 查询字段，包含 DISTINCT、聚合字段与子查询字段
+    fn select_raw(&self, s: &Columns, args: &mut Args) -> Columns {
+        let mut columns = s.clone();
+        if let Props::Aggregate(list) = &self.inner[AGGREGATE] {
+            columns.extend(list.iter().cloned());
         }
-        raw.push_str(&raw!("SELECT {} FROM {}", columns, t));
-        let keys: Vec<_> = Raw::extend_map(RawType::Fetch, v, args, s);
-        if !keys.is_empty() {
-            raw.push_str(&raw!(" WHERE {}", keys.join(" AND ")));
+        if let Props::Sub(list) = &self.inner[SELECT_SUB] {
+            for (alias, sub) in list.iter() {
+                let (raw, list) = sub.into_raw(RawType::Fetch);
+                columns.push(raw!("({}) AS {}", raw, alias));
+                args.extend(list);
+            }
         }
+        if columns.is_empty() {
+            columns.push("*".into());
+        }
+        if let Props::Distinct = &self.inner[DISTINCT] {
+            columns[0] = raw!("DISTINCT {}", columns[0]);
+        }
+        columns
     }
 
     fn into_saving(raw: &mut String, args: &mut Vec<Value>, t: &str, s: &Columns, v: &Value) {
         raw.push_str(&raw!("INSERT INTO {}", t));
-        let keys = Raw::extend_map(RawType::Saving, &v, args, s);
+        let keys = Raw::extend_map(RawType::Saving, &v, args, s, true);
         if !keys.is_empty() {
             let hold: String = vec!["?"; keys.len()].join(", ");
             raw.push_str(&raw!("({}) VALUES ({})", keys.join(", "), hold));
         }
     }
 
-    fn into_update(raw: &mut String, args: &mut Vec<Value>, t: &str, s: &Columns, v: &Value) {
+    fn into_update(
+        raw: &mut String,
+        args: &mut Vec<Value>,
+        t: &str,
+        s: &Columns,
+        v: &Value,
+        plain: bool,
+        inc: &Columns,
+    ) {
         raw.push_str(&raw!("UPDATE {}", t));
-        let keys = Raw::extend_map(RawType::Update, v, args, s);
+        let mut keys = Raw::extend_map(RawType::Update, v, args, s, plain);
+        keys.extend(inc.iter().map(|v| raw!("{} = {} + 1", v, v)));
         if !keys.is_empty() {
             raw.push_str(&raw!(" SET {}", keys.join(" , ")));
         }
     }
 
-    fn into_delete(raw: &mut String, args: &mut Vec<Value>, t: &str, s: &Columns, v: &Value) {
+    fn into_delete(
+        raw: &mut String,
+        args: &mut Args,
+        t: &str,
+        s: &Columns,
+        v: &Value,
+        plain: bool,
+    ) -> Columns {
         raw.push_str(&raw!("DELETE FROM {}", t));
-        let keys = Raw::extend_map(RawType::Delete, v, args, s);
-        if !keys.is_empty() {
-            raw.push_str(&raw!(" WHERE {}", keys.join(" AND ")));
+        Raw::extend_map(RawType::Delete, v, args, s, plain)
+    }
+
+    // WITH 前缀，参数位于语句最前
+    fn with_raw(&self, t: &RawType, args: &mut Args) -> String {
+        let Props::With((recursive, list)) = &self.inner[WITH] else {
+            return String::new();
+        };
+        if t.is_saving() {
+            return String::new();
+        }
+        let mut items = vec![];
+        for (name, v) in list.iter() {
+            let (raw, list) = v.into_raw(RawType::Fetch);
+            items.push(raw!("{} AS ({})", name, raw));
+            args.extend(list);
+        }
+        let with = if *recursive { "WITH RECURSIVE" } else { "WITH" };
+        raw!("{} {} ", with, items.join(", "))
+    }
+
+    // 合并模型条件、where 条件、子查询条件与软删除范围
+    fn where_raw(&self, t: &RawType, model: &Value, args: &mut Args, mut conds: Columns) -> String {
+        if t.is_saving() {
+            return String::new();
+        }
+        let scope = match &self.inner[TRASHED] {
+            Props::Trashed((c, Trashed::Without)) => raw!("{} IS NULL", c),
+            Props::Trashed((c, Trashed::Only)) => raw!("{} IS NOT NULL", c),
+            _ => String::new(),
+        };
+        if let Props::Where((s, list)) = &self.inner[WHERE] {
+            if t.is_single_prop() && !model.is_null() && !s.is_empty() && list.is_empty() {
+                if let Some(v) = model
+                    .as_map()
+                    .and_then(|v| v.0.get(&Value::String(s.into())))
+                {
+                    conds.push(raw!("{} = ?", s));
+                    args.push(v.clone());
+                }
+            } else if !s.is_empty() {
+                // 与其他条件组合时，条件中可能包含 OR
+                let alone = !matches!(&self.inner[IN], Props::In(v) if !v.is_empty())
+                    && !matches!(&self.inner[SUB], Props::Sub(_));
+                if alone && conds.is_empty() && scope.is_empty() {
+                    conds.push(s.clone());
+                } else {
+                    conds.push(raw!("({})", s));
+                }
+                list.iter().for_each(|v| args.push(v.clone()));
+            }
+        }
+        if let Props::In(list) = &self.inner[IN] {
+            for (column, not, list) in list.iter() {
+                conds.push(match (list.is_empty(), not) {
+                    (true, false) => "1 = 0".into(),
+                    (true, true) => "1 = 1".into(),
+                    (false, not) => {
+                        let hold = vec!["?"; list.len()].join(", ");
+                        let not = if *not { "NOT " } else { "" };
+                        raw!("{} {}IN ({})", column, not, hold)
+                    }
+                });
+                list.iter().for_each(|v| args.push(v.clone()));
+            }
         }
+        if let Props::Sub(list) = &self.inner[SUB] {
+            for (cond, sub) in list.iter() {
+                let (raw, list) = sub.into_raw(RawType::Fetch);
+                conds.push(raw!("{} ({})", cond, raw));
+                args.extend(list);
+            }
+        }
+        if !scope.is_empty() {
+            conds.push(scope);
+        }
+        if conds.is_empty() {
+            return String::new();
+        }
+        raw!(" WHERE {}", conds.join(" AND "))
     }
 }
 
 impl IntoRaw for Raw {
     fn into_raw(&self, v: RawType) -> (String, Vec<crate::Value>) {
         let table = &self.table;
-        let mut raw = String::new();
         let mut args: Vec<crate::Value> = vec![];
+        let mut raw = self.with_raw(&v, &mut args);
 
-        let mut model = &crate::Value::Null;
-        if let Props::Model(v) = &self.inner[0] {
-            model = v;
-        }
+        let (model, plain) = match &self.inner[MODEL] {
+            Props::Model(v) => (v, false),
+            Props::Values(v) => (v, true),
+            _ => (&crate::Value::Null, false),
+        };
         let mut columns: Columns = vec![];
-        if let Props::Select(s) = &self.inner[1] {
+        if let Props::Select(s) = &self.inner[SELECT] {
             columns = s.clone()
         }
-        match &v {
-            RawType::Fetch => Raw::into_fetch(&mut raw, &mut args, table, &columns, model),
-            RawType::Saving => Raw::into_saving(&mut raw, &mut args, table, &columns, model),
-            RawType::Update => Raw::into_update(&mut raw, &mut args, table, &columns, model),
-            RawType::Delete => Raw::into_delete(&mut raw, &mut args, table, &columns, model),
+        let mut increase: Columns = vec![];
+        if let Props::Increase(list) = &self.inner[INCREASE] {
+            increase = list.clone()
+        }
+        let conds = match &v {
+            RawType::Fetch => {
+                let select = self.select_raw(&columns, &mut args);
+                Raw::into_fetch(&mut raw, &mut args, table, &select, &columns, model, plain)
+            }
+            RawType::Delete => Raw::into_delete(&mut raw, &mut args, table, &columns, model, plain),
+            RawType::Saving => {
+                Raw::into_saving(&mut raw, &mut args, table, &columns, model);
+                vec![]
+            }
+            RawType::Update => {
+                let (s, v) = (&columns, model);
+                Raw::into_update(&mut raw, &mut args, table, s, v, plain, &increase);
+                vec![]
+            }
         };
+        raw.push_str(&self.where_raw(&v, model, &mut args, conds));
 
-        self.inner.iter().for_each(|p| match p {
-            Props::Where((s, list)) => {
-                if !v.is_saving() {
-                    if v.is_single_prop() && !model.is_null() && !s.is_empty() && list.is_empty() {
-                        if let crate::Value::Map(dict) = model {
-                            let key = Value::String(s.into());
-                            if let Some(v) = dict.0.get(&key) {
-                                raw.push_str(&raw!(" WHERE {} = ?", s));
-                                args.push(v.clone());
-                            }
-                        }
-                    } else {
-                        raw.push_str(&raw!(" WHERE {}", s));
-                        list.iter().for_each(|v| args.push(v.clone()));
-                    }
-                }
+        if !v.is_fetch() {
+            return (raw, args);
+        }
+        if let Props::Group(c) = &self.inner[GROUP] {
+            raw.push_str(&raw!(" GROUP BY {}", c));
+        }
+        if let Props::Having((s, list)) = &self.inner[HAVING] {
+            raw.push_str(&raw!(" HAVING {}", s));
+            list.iter().for_each(|v| args.push(v.clone()));
+        }
+        if let Props::Compound(list) = &self.inner[COMPOUND] {
+            for (op, v) in list.iter() {
+                let (sub, list) = v.into_raw(RawType::Fetch);
+                raw.push_str(&raw!(" {} {}", op, sub));
+                args.extend(list);
             }
-            Props::Group(c) => {
-                if v.is_fetch() {
-                    raw.push_str(&raw!(" GROUP BY {}", c));
-                }
+        }
+        if let Props::Order(c) = &self.inner[ORDER] {
+            raw.push_str(&raw!(" ORDER BY {}", c));
+        }
+        if let Props::Limit((l, o)) = &self.inner[LIMIT] {
+            if *o == 0 {
+                raw.push_str(&raw!(" LIMIT {}", l));
+            } else {
+                raw.push_str(&raw!(" LIMIT {},{}", l, o));
             }
-            Props::Order(c) => {
-                if v.is_fetch() {
-                    raw.push_str(&raw!(" ORDER BY {}", c));
-                }
+        }
+        (raw, args)
+    }
+
+    // 拆分最长的 IN 列表，只拆分不含排序、分页、分组、聚合等子句的语句
+    fn split_raw(&self, v: RawType) -> crate::Result<Vec<(String, Vec<crate::Value>)>> {
+        let raw = self.into_raw(v);
+        if raw.1.len() <= MAX_ARGS || v.is_saving() {
+            return Ok(vec![raw]);
+        }
+        let err = |v: &str| raw!("too many arguments: {}, {}", raw.1.len(), v);
+        // 拆分后各语句的结果无法正确合并
+        let clauses = [
+            (GROUP, "GROUP BY"),
+            (ORDER, "ORDER BY"),
+            (LIMIT, "LIMIT"),
+            (HAVING, "HAVING"),
+            (DISTINCT, "DISTINCT"),
+            (AGGREGATE, "aggregate"),
+            (SUB, "subquery"),
+            (SELECT_SUB, "subquery"),
+            (COMPOUND, "compound select"),
+            (WITH, "WITH"),
+        ];
+        for (i, clause) in clauses {
+            if !matches!(self.inner[i], Props::Empty) {
+                return Err(err(&raw!("a query with {} cannot be split", clause)).into());
             }
-            Props::Limit((l, o)) => {
-                if v.is_fetch() {
-                    if *o == 0 {
-                        raw.push_str(&raw!(" LIMIT {}", l));
-                    } else {
-                        raw.push_str(&raw!(" LIMIT {},{}", l, o));
-                    }
+        }
+        let Props::In(list) = &self.inner[IN] else {
+            return Err(err("only IN lists can be split").into());
+        };
+        let Some((i, (_, _, args))) = list
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| !v.1)
+            .max_by_key(|(_, v)| v.2.len())
+        else {
+            return Err(err("NOT IN lists cannot be split").into());
+        };
+        let rest = raw.1.len() - args.len();
+        if rest >= MAX_ARGS {
+            return Err(err("arguments outside the IN list exceed the limit").into());
+        }
+        let size = MAX_ARGS - rest;
+        let list = args
+            .chunks(size)
+            .map(|chunk| {
+                let mut raw = self.clone();
+                if let Props::In(list) = &mut raw.inner[IN] {
+                    list[i].2 = chunk.to_vec();
                 }
-            }
-            _ => {}
-        });
-        (raw, args)
+                raw.into_raw(v)
+            })
+            .collect();
+        Ok(list)
     }
 }
 
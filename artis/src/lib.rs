@@ -3,6 +3,8 @@ mod artis_tx;
 mod decode;
 mod error;
 mod into_raw;
+mod model;
+pub mod relation;
 mod types;
 
 pub mod driver;
@@ -15,7 +17,9 @@ pub use artis::{Artis, ArtisExecutor};
 pub use artis_tx::{ArtisTx, ArtisTxExecutor};
 pub use driver::Value;
 pub use error::Error;
-pub use into_raw::{IntoLimit, IntoRaw, IntoTable, Raw};
+pub use into_raw::{IntoColumn, IntoLimit, IntoRaw, IntoTable, Raw};
+pub use model::{from_value, Column, Hooks, Model};
+pub use relation::Eager;
 pub use types::{BoxFuture, ExecResult, IntoArtis, IntoChunk, RawType};
 
 #[cfg(feature = "derive")]
@@ -28,6 +32,15 @@ macro_rules! meta {
     };
 }
 
+// Rust 迁移，闭包源码参与校验和计算，修改后 migrate_up 返回错误
+#[macro_export]
+macro_rules! migration {
+    ($version:expr, $name:expr, $up:expr, $down:expr $(,)?) => {
+        $crate::migrator::Migration::func($version, $name, $up, $down)
+            .source(concat!(stringify!($up), stringify!($down)))
+    };
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[macro_export]
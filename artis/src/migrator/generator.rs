@@ -0,0 +1,239 @@
+use crate::raw;
+
+use super::{DriverMigrator, IndexMeta, Mapping, TableMeta};
+
+// 反向映射时优先使用的类型
+const PRIORITY: [&str; 21] = [
+    "String",
+    "i64",
+    "i32",
+    "u64",
+    "u32",
+    "f64",
+    "f32",
+    "bool",
+    "i16",
+    "u16",
+    "i8",
+    "u8",
+    "Bytes",
+    "DateTime",
+    "NaiveDateTime",
+    "NaiveDate",
+    "NaiveTime",
+    "Date",
+    "Time",
+    "Decimal",
+    "Uuid",
+];
+
+// 映射键对应的 Rust 类型，rbdc 类型优先
+fn rust_path(k: &str) -> String {
+    #[cfg(feature = "rbdc")]
+    if let "Date" | "Time" | "DateTime" | "Timestamp" | "Decimal" | "Uuid" = k {
+        return raw!("rbatis::rbdc::types::{}", k);
+    }
+    // DateTime 为内置映射，未启用 chrono 时使用 rbdc 类型
+    #[cfg(not(feature = "chrono"))]
+    if k == "DateTime" {
+        return raw!("rbatis::rbdc::types::{}", k);
+    }
+    let v = match k {
+        "Bytes" => "Vec<u8>",
+        "NaiveDate" | "NaiveTime" | "NaiveDateTime" => return raw!("chrono::{}", k),
+        "DateTime" => "chrono::DateTime<chrono::Utc>",
+        "Decimal" => "rust_decimal::Decimal",
+        "Uuid" => "uuid::Uuid",
+        _ => k,
+    };
+    v.into()
+}
+
+// 迁移器内部使用的表
+const INTERNAL: [&str; 2] = ["artis_migrations", "artis_lock"];
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "yield",
+];
+
+fn base_type(v: &str) -> String {
+    let v = v.trim().to_uppercase();
+    let v = v.split('(').next().unwrap_or_default();
+    v.split_whitespace().next().unwrap_or_default().into()
+}
+
+// 由字段类型反推 Rust 类型，返回 (类型, 是否需要指定 type)
+fn rust_type(dict: &Mapping, colume: &str) -> (String, bool) {
+    let mut keys: Vec<_> = dict
+        .keys()
+        .filter(|v| !PRIORITY.contains(v) && **v != "Vec" && **v != "Map")
+        .copied()
+        .collect();
+    keys.sort();
+    let keys: Vec<_> = PRIORITY.iter().copied().chain(keys).collect();
+    let colume = colume.to_uppercase();
+    for k in keys.iter().filter(|k| dict.contains_key(*k)) {
+        if dict[k].to_uppercase() == colume {
+            return (rust_path(k), false);
+        }
+    }
+    let base = base_type(&colume);
+    for k in keys.iter().filter(|k| dict.contains_key(*k)) {
+        if base_type(dict[k]) == base {
+            return (rust_path(k), true);
+        }
+    }
+    if base.contains("CHAR") || base.contains("TEXT") {
+        return ("String".into(), true);
+    }
+    ("rbs::Value".into(), true)
+}
+
+// 表名转为 PascalCase，不做单复数转换，如 persons 生成 Persons
+fn struct_name(table: &str) -> String {
+    table
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            let mut c = v.chars();
+            let first = c.next().unwrap().to_uppercase().to_string();
+            first + c.as_str()
+        })
+        .collect()
+}
+
+// 字段名不是合法标识符时转为 snake_case，由 serde rename 映射回原字段
+fn field_name(colume: &str) -> String {
+    let mut name = String::new();
+    let mut prev = ' ';
+    for c in colume.chars() {
+        if c.is_ascii_uppercase() && (prev.is_ascii_lowercase() || prev.is_ascii_digit()) {
+            name.push('_');
+        }
+        prev = c;
+        match c {
+            c if c.is_ascii_alphanumeric() => name.push(c.to_ascii_lowercase()),
+            _ if name.ends_with('_') => {}
+            _ => name.push('_'),
+        }
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        return raw!("r#{}", name);
+    }
+    name
+}
+
+fn literal(v: &str) -> String {
+    raw!("\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn default_literal(v: &str) -> String {
+    if v.parse::<f64>().is_ok() {
+        return v.into();
+    }
+    let is_raw = v.chars().all(|c| c.is_ascii_uppercase() || c == '_');
+    if is_raw {
+        return v.into();
+    }
+    literal(v.trim_matches('\''))
+}
+
+// 注释生成为文档注释，derive 会读取为 comment
+fn doc(v: &str, indent: &str) -> String {
+    v.lines()
+        .map(|v| raw!("{}/// {}\n", indent, v.trim()))
+        .collect()
+}
+
+impl TableMeta {
+    pub fn to_rust<'a>(&self, m: &(dyn DriverMigrator<'a> + '_)) -> String {
+        let dict = m.types();
+        let mut raw = String::new();
+        raw.push_str(&doc(&self.comment, ""));
+        raw.push_str("#[derive(Debug, Serialize, Deserialize, artis::Artis)]\n");
+        // 生成的结构体由 Option<T> 推断可空性
+        let table = literal(&self.name);
+        raw.push_str(&raw!("#[artis(table = {}, infer_nullable)]\n", table));
+        raw.push_str(&raw!("pub struct {} {{\n", struct_name(&self.name)));
+        if self.primary.contains(',') {
+            raw.push_str(&raw!("    // primary key: {}\n", self.primary));
+        }
+        for inx in self.indexs.iter().filter(|v| v.column().contains(',')) {
+            raw.push_str(&raw!("    // {}: {}\n", inx.name(&self.name), inx.column()));
+        }
+        for v in self.foreigns.iter() {
+            raw.push_str(&raw!(
+                "    // foreign key: {} -> {}({})\n",
+                v.colume,
+                v.table,
+                v.refer
+            ));
+        }
+        for v in self.columes.iter() {
+            let mut attrs: Vec<String> = vec![];
+            let is_primary = v.name == self.primary;
+            let is_increment = v.increment || v.colume == "SERIAL";
+            let (mut typ, is_typed) = rust_type(&dict, &v.colume);
+            if is_primary {
+                attrs.push("PRIMARY".into());
+            }
+            if is_increment {
+                attrs.push("AUTO_INCREMENT".into());
+                typ = "i64".into();
+            } else if is_typed {
+                attrs.push(raw!("type = {}", literal(&v.colume)));
+            }
+            for inx in self.indexs.iter() {
+                match inx {
+                    IndexMeta::Index(c) if *c == v.name => attrs.push("INDEX".into()),
+                    IndexMeta::Unique(c) if *c == v.name => attrs.push("UNIQUE".into()),
+                    _ => {}
+                }
+            }
+            // 可空由 Option<T> 推断，自增字段总是 Option<T>
+            if !v.nullable && !is_primary && is_increment {
+                attrs.push("NOT_NULL".into());
+            }
+            let is_stamp = matches!(v.name.as_str(), "created_at" | "updated_at");
+            if is_stamp && v.default == "CURRENT_TIMESTAMP" {
+                attrs.push(v.name.clone());
+            } else if !v.default.is_empty() {
+                attrs.push(raw!("default = {}", default_literal(&v.default)));
+            }
+            raw.push_str(&doc(&v.comment, "    "));
+            if !attrs.is_empty() {
+                raw.push_str(&raw!("    #[artis({})]\n", attrs.join(", ")));
+            }
+            if v.nullable || is_increment {
+                typ = raw!("Option<{}>", typ);
+            }
+            let name = field_name(&v.name);
+            if name.trim_start_matches("r#") != v.name {
+                raw.push_str(&raw!("    #[serde(rename = {})]\n", literal(&v.name)));
+            }
+            raw.push_str(&raw!("    pub {}: {},\n", name, typ));
+        }
+        raw.push_str("}\n");
+        raw
+    }
+}
+
+// 由数据库表结构生成 Rust 结构体
+// 自增字段固定生成 Option<i64>；BLOB 等二进制字段生成 Vec<u8>，不会还原为 JSON 字段
+pub fn generate<'a>(m: &(dyn DriverMigrator<'a> + '_), metas: &[TableMeta]) -> String {
+    let mut raw = String::from("use serde::{Deserialize, Serialize};\n");
+    for v in metas.iter() {
+        if INTERNAL.contains(&v.name.as_str()) {
+            continue;
+        }
+        raw.push('\n');
+        raw.push_str(&v.to_rust(m));
+    }
+    raw
+}
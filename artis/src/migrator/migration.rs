@@ -0,0 +1,403 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Deserialize;
+
+use crate::{raw, rbv, Artis, ArtisTx, BoxFuture, IntoArtis, Raw, Result};
+
+use super::{migrator::query_all, ColumeMeta, DriverMigrator, TableMeta};
+
+const HISTORY: &str = "artis_migrations";
+
+// 文件以该注释开头时不拆分语句，用于触发器等包含分号的语句
+const NO_SPLIT: &str = "-- artis:no-split";
+
+pub type MigrateFn = Arc<dyn Fn(Arc<ArtisTx>) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+#[derive(Clone)]
+pub enum Step {
+    Raw(Vec<String>),
+    // 总是在事务中执行
+    Func(MigrateFn),
+}
+
+#[derive(Clone)]
+pub struct Migration {
+    pub version: u64, // 版本号
+    pub name: String, // 名称
+    pub up: Step,     // 升级
+    pub down: Step,   // 回滚
+    // Rust 迁移的源码，参与校验和计算，由 migration! 填充
+    pub source: String,
+}
+
+impl std::fmt::Debug for Migration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Migration")
+            .field("version", &self.version)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl Migration {
+    pub fn raw(version: u64, name: &str, up: &str, down: &str) -> Self {
+        Self {
+            version,
+            name: name.into(),
+            up: Step::Raw(split_raw(up)),
+            down: Step::Raw(split_raw(down)),
+            source: String::new(),
+        }
+    }
+
+    pub fn func<U, UF, D, DF>(version: u64, name: &str, up: U, down: D) -> Self
+    where
+        U: Fn(Arc<ArtisTx>) -> UF + Send + Sync + 'static,
+        UF: Future<Output = Result<()>> + Send + 'static,
+        D: Fn(Arc<ArtisTx>) -> DF + Send + Sync + 'static,
+        DF: Future<Output = Result<()>> + Send + 'static,
+    {
+        Self {
+            version,
+            name: name.into(),
+            up: Step::Func(Arc::new(move |rb| Box::pin(up(rb)))),
+            down: Step::Func(Arc::new(move |rb| Box::pin(down(rb)))),
+            source: String::new(),
+        }
+    }
+
+    // 闭包无法计算校验和，修改 Rust 迁移时需同步修改 source
+    pub fn source(mut self, v: &str) -> Self {
+        self.source = v.into();
+        self
+    }
+
+    // 读取目录下的 {version}_{name}.up.sql 与 {version}_{name}.down.sql，两者缺一不可
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Vec<Migration>> {
+        type Files = (String, Option<String>, Option<String>);
+        let mut dict: HashMap<u64, Files> = HashMap::new();
+        for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            let file = path.file_name().unwrap_or_default().to_string_lossy();
+            let (stem, up) = if let Some(v) = file.strip_suffix(".up.sql") {
+                (v.to_string(), true)
+            } else if let Some(v) = file.strip_suffix(".down.sql") {
+                (v.to_string(), false)
+            } else {
+                continue;
+            };
+            let Some((version, name)) = stem.split_once('_') else {
+                return Err(raw!("invalid migration file: {}", file).into());
+            };
+            let Ok(version) = version.parse::<u64>() else {
+                return Err(raw!("invalid migration version: {}", file).into());
+            };
+            let raw = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            let item = dict.entry(version).or_insert((name.into(), None, None));
+            let step = if up { &mut item.1 } else { &mut item.2 };
+            if item.0 != name || step.is_some() {
+                return Err(raw!("duplicate migration version: {}", file).into());
+            }
+            *step = Some(raw);
+        }
+        let mut list = vec![];
+        for (version, (name, up, down)) in dict {
+            let (Some(up), Some(down)) = (up, down) else {
+                let msg = raw!(
+                    "migration {}_{} requires both up and down files",
+                    version,
+                    name
+                );
+                return Err(msg.into());
+            };
+            list.push(Migration::raw(version, &name, &up, &down));
+        }
+        list.sort_by_key(|v| v.version);
+        Ok(list)
+    }
+
+    pub fn checksum(&self) -> String {
+        let mut raw = raw!("{}:{}", self.version, self.name);
+        for step in [&self.up, &self.down] {
+            if let Step::Raw(list) = step {
+                list.iter().for_each(|v| raw.push_str(v));
+            }
+            raw.push('\n');
+        }
+        // 忽略空白，避免源码格式变化导致校验和不一致
+        if !self.source.is_empty() {
+            raw.extend(self.source.chars().filter(|v| !v.is_whitespace()));
+        }
+        // FNV-1a，保证跨版本稳定
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for b in raw.as_bytes() {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        raw!("{:016x}", hash)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    Changed, // 已执行的迁移被修改
+    Missing, // 已执行但本地不存在
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: u64,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Applied {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: i64,
+}
+
+fn history() -> TableMeta {
+    let colume = |name: &str, colume: &str| ColumeMeta {
+        name: name.into(),
+        colume: colume.into(),
+        ..Default::default()
+    };
+    TableMeta {
+        name: HISTORY.into(),
+        primary: "version".into(),
+        columes: vec![
+            colume("version", ":i64"),
+            colume("name", ":String"),
+            colume("checksum", ":String"),
+            colume("applied_at", ":i64"),
+        ],
+        ..Default::default()
+    }
+}
+
+// postgres 的 $$ 或 $tag$
+fn dollar_tag(v: &str) -> Option<&str> {
+    let body = v.strip_prefix('$')?;
+    let n = body.find('$')?;
+    let tag = &body[..n];
+    if tag.starts_with(|c: char| c.is_ascii_digit())
+        || !tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+    Some(&v[..n + 2])
+}
+
+// 按 `;` 拆分语句，忽略引号、注释与 $$ 内的分号，只有注释的语句不执行
+fn split_raw(v: &str) -> Vec<String> {
+    if v.trim_start().starts_with(NO_SPLIT) {
+        return vec![v.trim().to_string()];
+    }
+    let mut list = vec![];
+    let (mut start, mut i, mut code) = (0, 0, false);
+    while i < v.len() {
+        let rest = &v[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        if rest.starts_with("--") {
+            i += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+        if let Some(body) = rest.strip_prefix("/*") {
+            i += body.find("*/").map_or(rest.len(), |n| n + 4);
+            continue;
+        }
+        code |= !c.is_whitespace();
+        if let Some(tag) = dollar_tag(rest) {
+            let body = &rest[tag.len()..];
+            i += body.find(tag).map_or(rest.len(), |n| n + tag.len() * 2);
+        } else if matches!(c, '\'' | '"' | '`') {
+            i += rest[1..].find(c).map_or(rest.len(), |n| n + 2);
+        } else if c == ';' {
+            if code {
+                list.push(v[start..i].trim().to_string());
+            }
+            (start, code) = (i + 1, false);
+            i += 1;
+        } else {
+            i += c.len_utf8();
+        }
+    }
+    if code {
+        list.push(v[start..].trim().to_string());
+    }
+    list
+}
+
+async fn execute<R: IntoArtis>(rb: &R, v: &Migration, up: bool) -> Result<()> {
+    if let Step::Raw(list) = if up { &v.up } else { &v.down } {
+        for raw in list.iter() {
+            rb.exec(raw, vec![]).await?;
+        }
+    }
+    if !up {
+        rb.delete(&(HISTORY, rbv! {"version": v.version as i64,}))
+            .await?;
+        return Ok(());
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?;
+    let raw = rbv! {
+        "version": v.version as i64,
+        "name": v.name.clone(),
+        "checksum": v.checksum(),
+        "applied_at": now.as_secs() as i64,
+    };
+    rb.saving(&(HISTORY, raw)).await?;
+    Ok(())
+}
+
+impl<'a> Artis {
+    async fn applied(&'a self, m: &dyn DriverMigrator<'a>) -> Result<Vec<Applied>> {
+        self.auto_migrate(m, vec![history()]).await?;
+        self.fetch(&Raw::table(HISTORY).order("version")).await
+    }
+
+    // 每个迁移在持有迁移锁的事务中执行，获得锁后重新读取执行记录，已由其他实例处理时跳过
+    async fn migrate_step(
+        &'a self,
+        m: &dyn DriverMigrator<'a>,
+        v: &Migration,
+        up: bool,
+    ) -> Result<bool> {
+        let rb = Arc::new(self.begin().await?);
+        let chunk = async {
+            query_all(&rb, m.lock()?).await?;
+            let rst = async {
+                let raw =
+                    Raw::table(HISTORY).where_("version = ?", vec![rbs::value!(v.version as i64)]);
+                let applied: Vec<Applied> = rb.fetch(&raw).await?;
+                match applied.first() {
+                    Some(a) if a.checksum != v.checksum() => {
+                        return Err(
+                            raw!("migration {}_{} has been changed", v.version, v.name).into()
+                        )
+                    }
+                    Some(_) if up => return Ok(false),
+                    None if !up => return Ok(false),
+                    _ => {}
+                }
+                if let Step::Func(func) = if up { &v.up } else { &v.down } {
+                    func(Arc::clone(&rb)).await?;
+                }
+                execute(rb.as_ref(), v, up).await?;
+                Ok(true)
+            }
+            .await;
+            let unlock = query_all(&rb, m.unlock()?).await;
+            unlock.and(rst)
+        };
+        rb.chunk(chunk).await
+    }
+
+    pub async fn migrate_up(
+        &'a self,
+        m: &dyn DriverMigrator<'a>,
+        list: &[Migration],
+    ) -> Result<Vec<u64>> {
+        let applied: HashMap<_, _> = self
+            .applied(m)
+            .await?
+            .into_iter()
+            .map(|v| (v.version as u64, v))
+            .collect();
+        let mut pending = vec![];
+        for v in list.iter() {
+            match applied.get(&v.version) {
+                Some(a) if a.checksum != v.checksum() => {
+                    return Err(raw!("migration {}_{} has been changed", v.version, v.name).into())
+                }
+                Some(_) => {}
+                None => pending.push(v),
+            }
+        }
+        pending.sort_by_key(|v| v.version);
+        let mut versions = vec![];
+        for v in pending {
+            if self.migrate_step(m, v, true).await? {
+                versions.push(v.version);
+            }
+        }
+        Ok(versions)
+    }
+
+    pub async fn migrate_down(
+        &'a self,
+        m: &dyn DriverMigrator<'a>,
+        list: &[Migration],
+        n: usize,
+    ) -> Result<Vec<u64>> {
+        let dict: HashMap<_, _> = list.iter().map(|v| (v.version, v)).collect();
+        let applied = self.applied(m).await?;
+        let mut versions = vec![];
+        for a in applied.iter().rev().take(n) {
+            let Some(v) = dict.get(&(a.version as u64)) else {
+                return Err(raw!("migration {}_{} not found", a.version, a.name).into());
+            };
+            if a.checksum != v.checksum() {
+                return Err(raw!("migration {}_{} has been changed", v.version, v.name).into());
+            }
+            if self.migrate_step(m, v, false).await? {
+                versions.push(v.version);
+            }
+        }
+        Ok(versions)
+    }
+
+    pub async fn migrate_status(
+        &'a self,
+        m: &dyn DriverMigrator<'a>,
+        list: &[Migration],
+    ) -> Result<Vec<MigrationStatus>> {
+        let applied = self.applied(m).await?;
+        let dict: HashMap<_, _> = applied.iter().map(|v| (v.version as u64, v)).collect();
+        let mut status: Vec<_> = list
+            .iter()
+            .map(|v| {
+                let (state, applied_at) = match dict.get(&v.version) {
+                    None => (MigrationState::Pending, None),
+                    Some(a) if a.checksum != v.checksum() => {
+                        (MigrationState::Changed, Some(a.applied_at))
+                    }
+                    Some(a) => (MigrationState::Applied, Some(a.applied_at)),
+                };
+                MigrationStatus {
+                    version: v.version,
+                    name: v.name.clone(),
+                    state,
+                    applied_at,
+                }
+            })
+            .collect();
+        for a in applied.iter() {
+            if list.iter().any(|v| v.version == a.version as u64) {
+                continue;
+            }
+            status.push(MigrationStatus {
+                version: a.version as u64,
+                name: a.name.clone(),
+                state: MigrationState::Missing,
+                applied_at: Some(a.applied_at),
+            });
+        }
+        status.sort_by_key(|v| v.version);
+        Ok(status)
+    }
+}
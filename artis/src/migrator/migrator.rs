@@ -1,8 +1,8 @@
 use std::{collections::HashMap, fmt::Debug};
 
-use crate::{Artis, BoxFuture, IntoArtis, Result};
+use crate::{Artis, ArtisTx, BoxFuture, IntoArtis, Result, Value};
 
-use super::{types::Adjust, ColumeMeta, IndexMeta, TableMeta};
+use super::{types::Adjust, Change, ColumeMeta, IndexMeta, Mapping, Plan, TableMeta};
 
 pub trait ArtisMigrator: Sized {
     fn migrator() -> TableMeta;
@@ -10,27 +10,98 @@ pub trait ArtisMigrator: Sized {
 
 pub trait DriverMigrator<'a>: Debug + Send + Sync + 'a {
     fn mapping(&self, meta: &mut TableMeta);
+    // Rust 类型与字段类型的映射
+    fn types(&self) -> Mapping {
+        Mapping::new()
+    }
     fn create_table(&self, meta: &TableMeta) -> Result<String>;
+    // 建表的全部语句，默认只有 create_table，需要额外语句(如注释)时重写
+    fn create_table_raws(&self, meta: &TableMeta) -> Result<Vec<String>> {
+        Ok(vec![self.create_table(meta)?])
+    }
+    // 修改表注释
+    fn comment_table(&self, meta: &TableMeta) -> Result<Vec<String>> {
+        let _ = meta;
+        Ok(vec![])
+    }
+    // 只修改字段注释
+    fn comment_colume(&self, t: &TableMeta, meta: &ColumeMeta) -> Result<Vec<String>> {
+        let _ = (t, meta);
+        Ok(vec![])
+    }
     fn colume_raw(&self, t: &TableMeta, v: Adjust, meta: &ColumeMeta) -> Result<Vec<String>>;
     fn drop_index(&self, t: &TableMeta, meta: &IndexMeta) -> Result<String>;
     fn create_index(&self, t: &TableMeta, meta: &IndexMeta) -> Result<String>;
     fn fetch_tables(&self, rb: &'a Artis) -> BoxFuture<'a, Result<Vec<TableMeta>>>;
+    // 在事务连接上读取表结构，auto_migrate 获得锁后在同一连接上重新读取
+    // 返回 None 时通过连接池读取
+    fn fetch_tables_tx<'b>(
+        &'b self,
+        rb: &'b ArtisTx,
+    ) -> Option<BoxFuture<'b, Result<Vec<TableMeta>>>> {
+        let _ = rb;
+        None
+    }
+    // DDL 是否可以在事务中执行
+    fn transactional(&self) -> bool {
+        false
+    }
+    // 迁移锁，防止多个实例同时迁移
+    fn lock(&self) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+    fn unlock(&self) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
 }
 
 type AlterIndex = Vec<(Adjust, IndexMeta)>;
-type AlterColume = Vec<(Adjust, ColumeMeta)>;
+type AlterColume = Vec<Alter>;
+
+enum Alter {
+    Table,
+    Comment,
+    Colume(Adjust, ColumeMeta),
+    CommentColume(ColumeMeta),
+    Index(Adjust, IndexMeta),
+}
+
+impl Alter {
+    // 字段定义不变时只修改注释，修改定义时注释随定义一起修改
+    fn colume(cmeta: &ColumeMeta, v: &ColumeMeta) -> Option<Alter> {
+        if cmeta.ne(v) {
+            return Some(Alter::Colume(Adjust::Alter, cmeta.clone()));
+        }
+        if cmeta.comment != v.comment {
+            return Some(Alter::CommentColume(cmeta.clone()));
+        }
+        None
+    }
+}
 
 impl TableMeta {
     fn patch(&self, meta: &TableMeta) -> Result<(AlterColume, AlterIndex)> {
         let mut columes: AlterColume = vec![];
-        let dict: HashMap<_, _> = meta.columes.iter().map(|v| (v.name.clone(), v)).collect();
+        let mut dict: HashMap<_, _> = meta.columes.iter().map(|v| (v.name.clone(), v)).collect();
         for cmeta in self.columes.iter() {
-            if !dict.contains_key(&cmeta.name) {
-                columes.push((Adjust::Add, cmeta.clone()));
+            if let Some(v) = dict.remove(&cmeta.name) {
+                columes.extend(Alter::colume(cmeta, v));
                 continue;
             }
-            if cmeta.ne(dict.get(&cmeta.name).unwrap()) {
-                columes.push((Adjust::Alter, cmeta.clone()));
+            if cmeta.rename.is_empty() || !dict.contains_key(&cmeta.rename) {
+                columes.push(Alter::Colume(Adjust::Add, cmeta.clone()));
+                continue;
+            }
+            let mut v = dict.remove(&cmeta.rename).unwrap().clone();
+            columes.push(Alter::Colume(Adjust::Rename, cmeta.clone()));
+            v.name = cmeta.name.clone();
+            columes.extend(Alter::colume(cmeta, &v));
+        }
+        if self.prune {
+            for cmeta in meta.columes.iter() {
+                if dict.contains_key(&cmeta.name) {
+                    columes.push(Alter::Colume(Adjust::Drop, cmeta.clone()));
+                }
             }
         }
 
@@ -49,49 +120,143 @@ impl TableMeta {
             }
             dict.remove(&column);
         }
-        for v in dict.values_mut() {
-            indexs.push((Adjust::Drop, v.clone()));
+        for v in meta.indexs.iter() {
+            if dict.contains_key(&v.column()) {
+                indexs.push((Adjust::Drop, v.clone()));
+            }
         }
         Ok((columes, indexs))
     }
+
+    fn alters(&self, meta: Option<&TableMeta>) -> Result<Vec<Alter>> {
+        let Some(meta) = meta else {
+            let mut alters = vec![Alter::Table];
+            for inx in self.indexs.iter() {
+                alters.push(Alter::Index(Adjust::Add, inx.clone()));
+            }
+            return Ok(alters);
+        };
+        let (columes, indexs) = self.patch(meta)?;
+        let mut alters = vec![];
+        if self.comment != meta.comment {
+            alters.push(Alter::Comment);
+        }
+        // 先删除索引，避免删除或重命名字段时索引冲突
+        for (t, inx) in indexs.iter().filter(|v| matches!(v.0, Adjust::Drop)) {
+            alters.push(Alter::Index(t.clone(), inx.clone()));
+        }
+        alters.extend(columes);
+        for (t, inx) in indexs.into_iter().filter(|v| matches!(v.0, Adjust::Add)) {
+            alters.push(Alter::Index(t, inx));
+        }
+        Ok(alters)
+    }
+
+    // 未指定迁移器时只生成变更，不生成语句
+    fn plan<'a>(
+        &self,
+        m: Option<&(dyn DriverMigrator<'a> + '_)>,
+        meta: Option<&TableMeta>,
+    ) -> Result<Vec<Plan>> {
+        let mut plans = vec![];
+        for v in self.alters(meta)? {
+            let change = match &v {
+                Alter::Table => Change::CreateTable,
+                Alter::Comment => Change::CommentTable,
+                Alter::Colume(t, cmeta) => {
+                    let name = cmeta.name.clone();
+                    match t {
+                        Adjust::Add => Change::AddColume(name),
+                        Adjust::Alter => Change::AlterColume(name),
+                        Adjust::Drop => Change::DropColume(name),
+                        Adjust::Rename => Change::RenameColume(cmeta.rename.clone(), name),
+                    }
+                }
+                Alter::CommentColume(cmeta) => Change::CommentColume(cmeta.name.clone()),
+                Alter::Index(Adjust::Drop, inx) => Change::DropIndex(inx.name(&self.name)),
+                Alter::Index(_, inx) => Change::CreateIndex(inx.name(&self.name)),
+            };
+            let raws = match (m, &v) {
+                (None, _) => vec![],
+                (Some(m), Alter::Table) => m.create_table_raws(self)?,
+                (Some(m), Alter::Comment) => m.comment_table(self)?,
+                (Some(m), Alter::Colume(t, cmeta)) => m.colume_raw(self, t.clone(), cmeta)?,
+                (Some(m), Alter::CommentColume(cmeta)) => m.comment_colume(self, cmeta)?,
+                (Some(m), Alter::Index(Adjust::Drop, inx)) => vec![m.drop_index(self, inx)?],
+                (Some(m), Alter::Index(_, inx)) => vec![m.create_index(self, inx)?],
+            };
+            plans.push(Plan::new(&self.name, change, raws));
+        }
+        Ok(plans)
+    }
 }
 
 impl<'a> Artis {
+    pub async fn plan_migrate(
+        &'a self,
+        m: &dyn DriverMigrator<'a>,
+        v: Vec<TableMeta>,
+    ) -> Result<Vec<Plan>> {
+        let list = m.fetch_tables(self).await?;
+        plan_tables(m, &list, v)
+    }
+
     pub async fn auto_migrate(
         &'a self,
         m: &dyn DriverMigrator<'a>,
         v: Vec<TableMeta>,
     ) -> Result<()> {
-        let list = m.fetch_tables(self).await?;
-        let dict: HashMap<_, _> = list.iter().map(|v| (&v.name, v)).collect();
-        let mut metas = v.clone();
-        metas.iter_mut().for_each(|v| m.mapping(v));
-        for v in metas.iter() {
-            if !dict.contains_key(&v.name) {
-                let raw = m.create_table(v)?;
-                let _ = self.exec(&raw, vec![]).await?;
-                for inx in v.indexs.iter() {
-                    let raw = m.create_index(v, inx)?;
-                    let _ = self.exec(&raw, vec![]).await?;
-                }
-                continue;
-            }
-            let (columes, indexs) = v.patch(dict[&v.name])?;
-            for (t, meta) in columes.iter() {
-                let raws: Vec<_> = m.colume_raw(&v, t.clone(), meta)?;
-                for raw in raws {
-                    let _ = self.exec(&raw, vec![]).await?;
-                }
-            }
-            for (t, meta) in indexs.iter() {
-                let raw = match t {
-                    Adjust::Add => m.create_index(&v, meta)?,
-                    Adjust::Drop => m.drop_index(&v, meta)?,
-                    _ => continue,
+        let rb = self.begin().await?;
+        let chunk = async {
+            query_all(&rb, m.lock()?).await?;
+            // 获得锁后在同一连接上重新读取表结构
+            let rst = async {
+                let list = match m.fetch_tables_tx(&rb) {
+                    Some(v) => v.await?,
+                    None => m.fetch_tables(self).await?,
                 };
-                let _ = self.exec(&raw, vec![]).await?;
+                for plan in plan_tables(m, &list, v)? {
+                    for raw in plan.raws.iter() {
+                        let _ = rb.exec(raw, vec![]).await?;
+                    }
+                }
+                Ok(())
             }
-        }
-        Ok(())
+            .await;
+            let unlock = query_all(&rb, m.unlock()?).await;
+            rst.and(unlock)
+        };
+        rb.chunk(chunk).await
+    }
+}
+
+fn plan_tables<'a>(
+    m: &(dyn DriverMigrator<'a> + '_),
+    list: &[TableMeta],
+    mut metas: Vec<TableMeta>,
+) -> Result<Vec<Plan>> {
+    let dict: HashMap<_, _> = list.iter().map(|v| (&v.name, v)).collect();
+    metas.iter_mut().for_each(|v| m.mapping(v));
+    let mut plans = vec![];
+    for v in metas.iter() {
+        plans.extend(v.plan(Some(m), dict.get(&v.name).copied())?);
+    }
+    Ok(plans)
+}
+
+pub(super) async fn query_all(rb: &ArtisTx, list: Vec<String>) -> Result<()> {
+    for raw in list {
+        let _ = rb.query(&(raw, Vec::<Value>::new())).await?;
+    }
+    Ok(())
+}
+
+// 离线对比两份表结构，复用 auto_migrate 的变更规则
+pub fn diff(old: &[TableMeta], new: &[TableMeta]) -> Result<Vec<Plan>> {
+    let dict: HashMap<_, _> = old.iter().map(|v| (&v.name, v)).collect();
+    let mut plans = vec![];
+    for v in new.iter() {
+        plans.extend(v.plan(None, dict.get(&v.name).copied())?);
     }
+    Ok(plans)
 }
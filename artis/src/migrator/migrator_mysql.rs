@@ -1,10 +1,10 @@
 use serde::Deserialize;
 
-use crate::{map, raw, Artis, BoxFuture, IntoArtis, Raw, Result};
+use crate::{map, raw, Artis, ArtisTx, BoxFuture, IntoArtis, Raw, Result};
 
 use super::{
     migrator::DriverMigrator,
-    types::{Adjust, Mapping},
+    types::{current_timestamp, quote_literal, Adjust, Mapping},
     ColumeMeta, IndexMeta, TableMeta,
 };
 
@@ -13,14 +13,36 @@ const INDEX_SELECT: &'static str = "TABLE_NAME,INDEX_NAME,NON_UNIQUE,COLUMN_NAME
 
 const COLUME: &'static str = "information_schema.columns";
 const COLUME_SELECT: &'static str =
-    "TABLE_NAME,COLUMN_NAME,COLUMN_TYPE,IS_NULLABLE,COLUMN_DEFAULT,COLUMN_COMMENT";
+    "TABLE_NAME,COLUMN_NAME,COLUMN_TYPE,IS_NULLABLE,COLUMN_DEFAULT,COLUMN_COMMENT,EXTRA";
 
-#[derive(Debug)]
-pub struct MysqlMigrator {}
+const TABLE: &str = "information_schema.TABLES";
+const TABLE_SELECT: &str = "TABLE_NAME,TABLE_COMMENT";
+
+const LOCK: &str = "artis_migrate";
+
+// 0.3 起含自定义映射，不再支持 `MysqlMigrator {}`，使用 new() 或 default() 构造
+#[derive(Debug, Default)]
+pub struct MysqlMigrator {
+    extra: super::Mapping, // 自定义类型映射
+}
 
 impl MysqlMigrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 注册自定义类型映射，覆盖内置映射
+    pub fn with_mapping(extra: super::Mapping) -> Self {
+        Self { extra }
+    }
+
     fn mapping() -> Mapping {
-        map! {
+        #[allow(unused_mut)]
+        let mut dict = map! {
+            "i8" : "TINYINT",
+            "i16" : "SMALLINT",
+            "u8" : "TINYINT UNSIGNED",
+            "u16" : "SMALLINT UNSIGNED",
             "i32" : "INT",
             "i64" : "BIGINT",
             "u32" : "INT",
@@ -30,8 +52,33 @@ impl MysqlMigrator {
             "Vec" : "JSON",
             "Map" : "JSON",
             "bool" : "TINYINT",
+            "Bytes" : "BLOB",
             "String" : "VARCHAR(255)",
-        }
+            "DateTime" : "DATETIME",
+        };
+        #[cfg(feature = "chrono")]
+        dict.extend(map! {
+            "NaiveDate" : "DATE",
+            "NaiveTime" : "TIME",
+            "NaiveDateTime" : "DATETIME",
+        });
+        #[cfg(feature = "uuid")]
+        dict.extend(map! {
+            "Uuid" : "CHAR(36)",
+        });
+        #[cfg(feature = "rust_decimal")]
+        dict.extend(map! {
+            "Decimal" : "DECIMAL(38,10)",
+        });
+        #[cfg(feature = "rbdc")]
+        dict.extend(map! {
+            "Date" : "DATE",
+            "Time" : "TIME",
+            "Timestamp" : "TIMESTAMP",
+            "Decimal" : "DECIMAL(38,10)",
+            "Uuid" : "CHAR(36)",
+        });
+        dict
     }
 }
 
@@ -49,22 +96,53 @@ struct Schema {
     pub default: Option<String>,
     #[serde(rename = "COLUMN_COMMENT", default)]
     pub comment: Option<String>,
+    #[serde(rename = "EXTRA", default)]
+    pub extra: Option<String>,
 }
 
-impl Into<ColumeMeta> for &Schema {
-    fn into(self) -> ColumeMeta {
+impl From<&Schema> for ColumeMeta {
+    fn from(v: &Schema) -> Self {
         ColumeMeta {
-            name: self.name.clone(),
+            name: v.name.clone(),
             size: 0,
-            colume: self.type_.clone().to_uppercase(),
-            nullable: self.nullable == "YES",
-            default: self.default.clone().unwrap_or_default(),
-            comment: self.comment.clone().unwrap_or_default(),
-            increment: false,
+            colume: v.type_.clone().to_uppercase(),
+            nullable: v.nullable == "YES",
+            default: current_timestamp(v.default.clone().unwrap_or_default()),
+            comment: v.comment.clone().unwrap_or_default(),
+            increment: v
+                .extra
+                .as_ref()
+                .is_some_and(|v| v.to_lowercase().contains("auto_increment")),
+            rename: "".into(),
         }
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct Table {
+    #[serde(rename = "TABLE_NAME")]
+    pub name: String,
+    #[serde(rename = "TABLE_COMMENT", default)]
+    pub comment: Option<String>,
+}
+
+// MySQL 默认将反斜杠视为转义符
+fn comment_literal(v: &str) -> String {
+    quote_literal(&v.replace('\\', "\\\\"))
+}
+
+// MODIFY 会覆盖完整定义，需保留 AUTO_INCREMENT
+fn colume_def(v: &ColumeMeta) -> String {
+    let mut raw = v.to_string();
+    if v.increment {
+        raw.push_str(" AUTO_INCREMENT");
+    }
+    if !v.comment.is_empty() {
+        raw.push_str(&raw!(" COMMENT {}", comment_literal(&v.comment)));
+    }
+    raw
+}
+
 #[derive(Debug, Deserialize)]
 struct Index {
     #[serde(rename = "TABLE_NAME")]
@@ -77,9 +155,67 @@ struct Index {
     pub colume: String,
 }
 
+async fn fetch_tables<R: IntoArtis>(rb: &R) -> Result<Vec<TableMeta>> {
+    let raw = Raw::table(COLUME)
+        .select(COLUME_SELECT.split(",").collect())
+        .where_("TABLE_SCHEMA = DATABASE()", vec![])
+        .order("TABLE_NAME");
+    let list: Vec<Schema> = rb.fetch(&raw).await?;
+    let mut metas: Vec<TableMeta> = vec![];
+    let mut meta = TableMeta::default();
+    for v in list.iter() {
+        if meta.name != v.table {
+            if !meta.name.is_empty() {
+                metas.push(meta);
+            }
+            meta = TableMeta::default();
+            meta.name = v.table.clone();
+        }
+        meta.columes.push(v.into());
+    }
+    if !meta.name.is_empty() {
+        metas.push(meta);
+    }
+
+    let raw = Raw::table(TABLE)
+        .select(TABLE_SELECT.split(",").collect())
+        .where_("TABLE_SCHEMA = DATABASE()", vec![]);
+    let list: Vec<Table> = rb.fetch(&raw).await?;
+    for v in list {
+        if let Some(meta) = metas.iter_mut().find(|m| m.name == v.name) {
+            meta.comment = v.comment.unwrap_or_default();
+        }
+    }
+
+    let raw = Raw::table(INDEX)
+        .select(INDEX_SELECT.split(",").collect())
+        .where_("TABLE_SCHEMA = DATABASE()", vec![])
+        .order("TABLE_NAME");
+    let list: Vec<Index> = rb.fetch(&raw).await?;
+    for v in list {
+        for meta in metas.iter_mut() {
+            if meta.name != v.table {
+                continue;
+            }
+            if v.name == "PRIMARY" {
+                meta.primary = v.colume;
+                break;
+            }
+            let inx = if v.unique == 0 {
+                IndexMeta::Unique(v.colume)
+            } else {
+                IndexMeta::Index(v.colume)
+            };
+            meta.indexs.push(inx);
+            break;
+        }
+    }
+    Ok(metas)
+}
+
 impl<'a> DriverMigrator<'a> for MysqlMigrator {
     fn mapping(&self, meta: &mut TableMeta) {
-        let dict = MysqlMigrator::mapping();
+        let dict = self.types();
         meta.columes.iter_mut().for_each(|v: &mut ColumeMeta| {
             if !v.colume.starts_with(":") {
                 return;
@@ -92,79 +228,73 @@ impl<'a> DriverMigrator<'a> for MysqlMigrator {
         })
     }
 
+    fn transactional(&self) -> bool {
+        false
+    }
+
+    fn lock(&self) -> Result<Vec<String>> {
+        Ok(vec![raw!("SELECT GET_LOCK('{}', -1)", LOCK)])
+    }
+
+    fn unlock(&self) -> Result<Vec<String>> {
+        Ok(vec![raw!("SELECT RELEASE_LOCK('{}')", LOCK)])
+    }
+
+    fn types(&self) -> super::Mapping {
+        let mut dict = MysqlMigrator::mapping();
+        dict.extend(self.extra.iter());
+        dict
+    }
+
     fn fetch_tables(&self, rb: &'a Artis) -> BoxFuture<'a, Result<Vec<TableMeta>>> {
-        let chunk = async move {
-            let raw = Raw::table(COLUME)
-                .select(COLUME_SELECT.split(",").collect())
-                .where_("TABLE_SCHEMA = DATABASE()", vec![])
-                .order("TABLE_NAME");
-            let list: Vec<Schema> = rb.fetch(&raw).await?;
-            let mut metas: Vec<TableMeta> = vec![];
-            let mut meta = TableMeta::default();
-            for v in list.iter() {
-                if meta.name != v.table {
-                    if !meta.name.is_empty() {
-                        metas.push(meta);
-                    }
-                    meta = TableMeta::default();
-                    meta.name = v.table.clone();
-                }
-                meta.columes.push(v.into());
-            }
-            if !meta.name.is_empty() {
-                metas.push(meta);
-            }
+        Box::pin(fetch_tables(rb))
+    }
 
-            let raw = Raw::table(INDEX)
-                .select(INDEX_SELECT.split(",").collect())
-                .where_("TABLE_SCHEMA = DATABASE()", vec![])
-                .order("TABLE_NAME");
-            let list: Vec<Index> = rb.fetch(&raw).await?;
-            for v in list {
-                for meta in metas.iter_mut() {
-                    if meta.name != v.table {
-                        continue;
-                    }
-                    if v.name == "PRIMARY" {
-                        meta.primary = v.colume;
-                        break;
-                    }
-                    let inx = if v.unique == 0 {
-                        IndexMeta::Unique(v.colume)
-                    } else {
-                        IndexMeta::Index(v.colume)
-                    };
-                    meta.indexs.push(inx);
-                    break;
-                }
-            }
-            Ok(metas)
-        };
-        Box::pin(chunk)
+    fn fetch_tables_tx<'b>(
+        &'b self,
+        rb: &'b ArtisTx,
+    ) -> Option<BoxFuture<'b, Result<Vec<TableMeta>>>> {
+        Some(Box::pin(fetch_tables(rb)))
     }
 
     fn create_table(&self, meta: &TableMeta) -> Result<String> {
-        let chunk = |v: &ColumeMeta| {
-            if !v.increment {
-                raw!("{}", v)
-            } else {
-                raw!("{} AUTO_INCREMENT", v)
-            }
-        };
-        let columes: Vec<_> = meta.columes.iter().map(chunk).collect();
+        let columes: Vec<_> = meta.columes.iter().map(colume_def).collect();
         let mut raw = raw!("CREATE TABLE {} ({})", meta.name, columes.join(", "));
         if !meta.primary.is_empty() {
             raw.truncate(raw.len() - 1);
             raw.push_str(&raw!(", PRIMARY KEY({}))", meta.primary));
         }
+        if !meta.comment.is_empty() {
+            raw.push_str(&raw!(" COMMENT = {}", comment_literal(&meta.comment)));
+        }
         Ok(raw)
     }
 
+    fn comment_table(&self, meta: &TableMeta) -> Result<Vec<String>> {
+        let comment = comment_literal(&meta.comment);
+        Ok(vec![raw!(
+            "ALTER TABLE {} COMMENT = {}",
+            meta.name,
+            comment
+        )])
+    }
+
+    // mysql 只能通过 MODIFY 完整定义修改注释
+    fn comment_colume(&self, t: &TableMeta, meta: &ColumeMeta) -> Result<Vec<String>> {
+        self.colume_raw(t, Adjust::Alter, meta)
+    }
+
     fn colume_raw(&self, t: &TableMeta, v: Adjust, meta: &ColumeMeta) -> Result<Vec<String>> {
         let raw = match v {
-            Adjust::Add => raw!("ALTER TABLE {} ADD {}", t.name, meta),
-            Adjust::Alter => raw!("ALTER TABLE {} MODIFY {}", t.name, meta),
-            _ => "".into(),
+            Adjust::Add => raw!("ALTER TABLE {} ADD {}", t.name, colume_def(meta)),
+            Adjust::Alter => raw!("ALTER TABLE {} MODIFY {}", t.name, colume_def(meta)),
+            Adjust::Drop => raw!("ALTER TABLE {} DROP COLUMN {}", t.name, meta.name),
+            Adjust::Rename => raw!(
+                "ALTER TABLE {} RENAME COLUMN {} TO {}",
+                t.name,
+                meta.rename,
+                meta.name
+            ),
         };
         Ok(vec![raw])
     }
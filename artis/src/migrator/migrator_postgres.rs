@@ -1,18 +1,30 @@
 use serde::Deserialize;
 
-use crate::{map, raw, Artis, BoxFuture, IntoArtis, IntoRaw, Raw, RawType, Result, Value};
+use crate::{map, raw, Artis, ArtisTx, BoxFuture, IntoArtis, IntoRaw, Raw, RawType, Result, Value};
 
-use super::{Adjust, ColumeMeta, DriverMigrator, IndexMeta, TableMeta};
+use super::{
+    types::{current_timestamp, quote_literal},
+    Adjust, ColumeMeta, DriverMigrator, IndexMeta, TableMeta,
+};
 
 const COLUME: &'static str = "information_schema.columns";
 const COLUME_SELECT: &'static str =
     "TABLE_NAME,COLUMN_NAME,UDT_NAME,CHARACTER_MAXIMUM_LENGTH,IS_NULLABLE,COLUMN_DEFAULT";
 
+const COLUME_COMMENT: &str = "col_description((quote_ident(table_schema) || '.' || quote_ident(table_name))::regclass, ordinal_position) AS column_comment";
+
+const TABLESQL: &str = "SELECT relname AS table_name, obj_description(oid, 'pg_class') AS table_comment FROM pg_class WHERE relkind = 'r' AND relnamespace = 'public'::regnamespace";
+
 // reference gorm by golang
 const INDEXSQL: &'static str = r#"SELECT ct.relname AS table_name,ci.relname AS index_name,i.indisunique AS non_unique,i.indisprimary AS primary,a.attname AS column_name FROM pg_index i LEFT JOIN pg_class ct ON ct.oid = i.indrelid LEFT JOIN pg_class ci ON ci.oid = i.indexrelid LEFT JOIN pg_attribute a ON a.attrelid = ct.oid LEFT JOIN pg_constraint con ON con.conindid = i.indexrelid WHERE a.attnum = ANY(i.indkey) AND con.oid IS NULL AND ct.relkind = 'r'"#;
 
-#[derive(Debug)]
-pub struct PostgresMigrator {}
+const LOCK: i64 = 0x6172746973; // "artis"
+
+// 0.3 起含自定义映射，不再支持 `PostgresMigrator {}`，使用 new() 或 default() 构造
+#[derive(Debug, Default)]
+pub struct PostgresMigrator {
+    extra: super::Mapping, // 自定义类型映射
+}
 
 #[derive(Debug, Deserialize)]
 struct Schema {
@@ -28,15 +40,38 @@ struct Schema {
     pub default: Option<String>,
     #[serde(rename = "character_maximum_length")]
     pub max_length: Option<usize>,
+    #[serde(rename = "column_comment", default)]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Table {
+    #[serde(rename = "table_name")]
+    pub name: String,
+    #[serde(rename = "table_comment", default)]
+    pub comment: Option<String>,
 }
 
-impl Into<ColumeMeta> for &Schema {
-    fn into(self) -> ColumeMeta {
-        let mut colume = self.type_.clone().to_uppercase();
-        if let Some(v) = self.max_length {
-            colume.push_str(&format!("({})", v));
+// 空注释时删除注释
+fn comment_literal(v: &str) -> String {
+    if v.is_empty() {
+        return "NULL".into();
+    }
+    quote_literal(v)
+}
+
+fn comment_colume(t: &TableMeta, v: &ColumeMeta) -> String {
+    let comment = comment_literal(&v.comment);
+    raw!("COMMENT ON COLUMN {}.{} IS {}", t.name, v.name, comment)
+}
+
+impl From<&Schema> for ColumeMeta {
+    fn from(v: &Schema) -> Self {
+        let mut colume = v.type_.clone().to_uppercase();
+        if let Some(n) = v.max_length {
+            colume.push_str(&format!("({})", n));
         }
-        let mut default = self.default.clone().unwrap_or_default();
+        let mut default = v.default.clone().unwrap_or_default();
         let is_increment = default.starts_with("nextval(");
         if is_increment {
             colume = "SERIAL".into();
@@ -45,14 +80,16 @@ impl Into<ColumeMeta> for &Schema {
         if default.contains("::character") {
             default = extract_range(&default, ("'", "'"));
         }
+        let default = current_timestamp(default);
         ColumeMeta {
-            name: self.name.clone(),
+            name: v.name.clone(),
             size: 0,
             colume,
-            nullable: self.nullable == "YES",
+            nullable: v.nullable == "YES",
             default,
-            comment: "".into(),
+            comment: v.comment.clone().unwrap_or_default(),
             increment: false,
+            rename: "".into(),
         }
     }
 }
@@ -60,11 +97,11 @@ impl Into<ColumeMeta> for &Schema {
 struct IndexRaw;
 
 impl IntoRaw for IndexRaw {
-    fn into_raw(&self, v: RawType) -> (&'static str, Vec<Value>) {
+    fn into_raw(&self, v: RawType) -> (String, Vec<Value>) {
         if !v.is_fetch() {
             panic!("Not Support");
         }
-        (INDEXSQL, vec![])
+        (INDEXSQL.into(), vec![])
     }
 }
 
@@ -81,8 +118,22 @@ struct Index {
 }
 
 impl PostgresMigrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 注册自定义类型映射，覆盖内置映射
+    pub fn with_mapping(extra: super::Mapping) -> Self {
+        Self { extra }
+    }
+
     fn mapping() -> super::Mapping {
-        map! {
+        #[allow(unused_mut)]
+        let mut dict = map! {
+            "i8" : "INT2",
+            "i16" : "INT2",
+            "u8" : "INT2",
+            "u16" : "INT4",
             "i32" : "INT4",
             "i64" : "INT8",
             "u32" : "INT4",
@@ -92,14 +143,91 @@ impl PostgresMigrator {
             "Vec" : "JSON",
             "Map" : "JSON",
             "bool" : "BOOLEAN",
+            "Bytes" : "BYTEA",
             "String" : "VARCHAR(255)",
+            "DateTime" : "TIMESTAMPTZ",
+        };
+        #[cfg(feature = "chrono")]
+        dict.extend(map! {
+            "NaiveDate" : "DATE",
+            "NaiveTime" : "TIME",
+            "NaiveDateTime" : "TIMESTAMP",
+        });
+        #[cfg(feature = "uuid")]
+        dict.extend(map! {
+            "Uuid" : "UUID",
+        });
+        #[cfg(feature = "rust_decimal")]
+        dict.extend(map! {
+            "Decimal" : "NUMERIC",
+        });
+        #[cfg(feature = "rbdc")]
+        dict.extend(map! {
+            "Date" : "DATE",
+            "Time" : "TIME",
+            "Timestamp" : "TIMESTAMP",
+            "Decimal" : "NUMERIC",
+            "Uuid" : "UUID",
+        });
+        dict
+    }
+}
+
+async fn fetch_tables<R: IntoArtis>(rb: &R) -> Result<Vec<TableMeta>> {
+    let inw = "TABLE_SCHEMA = 'public' AND TABLE_CATALOG = CURRENT_DATABASE()";
+    let mut select: Vec<_> = COLUME_SELECT.split(",").collect();
+    select.push(COLUME_COMMENT);
+    let raw = Raw::table(COLUME)
+        .select(select)
+        .where_(inw, vec![])
+        .order("TABLE_NAME");
+    let list: Vec<Schema> = rb.fetch(&raw).await?;
+    let mut metas: Vec<TableMeta> = vec![];
+    let mut meta = TableMeta::default();
+    for v in list.iter() {
+        if meta.name != v.table {
+            if !meta.name.is_empty() {
+                metas.push(meta);
+            }
+            meta = TableMeta::default();
+            meta.name = v.table.clone();
+        }
+        meta.columes.push(v.into());
+    }
+    if !meta.name.is_empty() {
+        metas.push(meta);
+    }
+    let list: Vec<Table> = rb.fetch(&(TABLESQL, Vec::<Value>::new())).await?;
+    for v in list {
+        if let Some(meta) = metas.iter_mut().find(|m| m.name == v.name) {
+            meta.comment = v.comment.unwrap_or_default();
+        }
+    }
+    let list: Vec<Index> = rb.fetch(&IndexRaw {}).await?;
+    for v in list {
+        for meta in metas.iter_mut() {
+            if meta.name != v.table {
+                continue;
+            }
+            if v.name == "PRIMARY" {
+                meta.primary = v.colume;
+                break;
+            }
+            let inx = if v.unique {
+                IndexMeta::Unique(v.colume)
+            } else {
+                IndexMeta::Index(v.colume)
+            };
+            meta.indexs.push(inx);
+            break;
         }
     }
+    Ok(metas)
 }
 
 impl<'a> DriverMigrator<'a> for PostgresMigrator {
     fn mapping(&self, meta: &mut TableMeta) {
-        let dict = PostgresMigrator::mapping();
+        let dict = self.types();
         meta.columes.iter_mut().for_each(|v: &mut ColumeMeta| {
             if !v.colume.starts_with(":") {
                 return;
@@ -116,51 +244,34 @@ impl<'a> DriverMigrator<'a> for PostgresMigrator {
         });
     }
 
+    fn transactional(&self) -> bool {
+        true
+    }
+
+    // 事务级锁，提交或回滚时释放，其他实例在 DDL 提交后才能读取表结构
+    fn lock(&self) -> Result<Vec<String>> {
+        Ok(vec![raw!("SELECT pg_advisory_xact_lock({})", LOCK)])
+    }
+
+    fn unlock(&self) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    fn types(&self) -> super::Mapping {
+        let mut dict = PostgresMigrator::mapping();
+        dict.extend(self.extra.iter());
+        dict
+    }
+
     fn fetch_tables(&self, rb: &'a Artis) -> BoxFuture<'a, Result<Vec<TableMeta>>> {
-        let chunk = async move {
-            let inw = "TABLE_SCHEMA = 'public' AND TABLE_CATALOG = CURRENT_DATABASE()";
-            let raw = Raw::table(COLUME)
-                .select(COLUME_SELECT.split(",").collect())
-                .where_(inw, vec![])
-                .order("TABLE_NAME");
-            let list: Vec<Schema> = rb.fetch(&raw).await?;
-            let mut metas: Vec<TableMeta> = vec![];
-            let mut meta = TableMeta::default();
-            for v in list.iter() {
-                if meta.name != v.table {
-                    if !meta.name.is_empty() {
-                        metas.push(meta);
-                    }
-                    meta = TableMeta::default();
-                    meta.name = v.table.clone();
-                }
-                meta.columes.push(v.into());
-            }
-            if !meta.name.is_empty() {
-                metas.push(meta);
-            }
-            let list: Vec<Index> = rb.fetch(&IndexRaw {}).await?;
-            for v in list {
-                for meta in metas.iter_mut() {
-                    if meta.name != v.table {
-                        continue;
-                    }
-                    if v.name == "PRIMARY" {
-                        meta.primary = v.colume;
-                        break;
-                    }
-                    let inx = if v.unique {
-                        IndexMeta::Unique(v.colume)
-                    } else {
-                        IndexMeta::Index(v.colume)
-                    };
-                    meta.indexs.push(inx);
-                    break;
-                }
-            }
-            Ok(metas)
-        };
-        Box::pin(chunk)
+        Box::pin(fetch_tables(rb))
+    }
+
+    fn fetch_tables_tx<'b>(
+        &'b self,
+        rb: &'b ArtisTx,
+    ) -> Option<BoxFuture<'b, Result<Vec<TableMeta>>>> {
+        Some(Box::pin(fetch_tables(rb)))
     }
 
     fn create_table(&self, meta: &TableMeta) -> Result<String> {
@@ -174,6 +285,27 @@ impl<'a> DriverMigrator<'a> for PostgresMigrator {
         Ok(raw)
     }
 
+    // postgres 的注释需要单独的 COMMENT ON 语句
+    fn create_table_raws(&self, meta: &TableMeta) -> Result<Vec<String>> {
+        let mut raws = vec![self.create_table(meta)?];
+        if !meta.comment.is_empty() {
+            raws.extend(self.comment_table(meta)?);
+        }
+        for v in meta.columes.iter().filter(|v| !v.comment.is_empty()) {
+            raws.push(comment_colume(meta, v));
+        }
+        Ok(raws)
+    }
+
+    fn comment_table(&self, meta: &TableMeta) -> Result<Vec<String>> {
+        let comment = comment_literal(&meta.comment);
+        Ok(vec![raw!("COMMENT ON TABLE {} IS {}", meta.name, comment)])
+    }
+
+    fn comment_colume(&self, t: &TableMeta, meta: &ColumeMeta) -> Result<Vec<String>> {
+        Ok(vec![comment_colume(t, meta)])
+    }
+
     fn colume_raw(&self, t: &TableMeta, v: Adjust, meta: &ColumeMeta) -> Result<Vec<String>> {
         let column = if meta.size == 0 {
             meta.colume.clone()
@@ -181,7 +313,13 @@ impl<'a> DriverMigrator<'a> for PostgresMigrator {
             raw!("{}({})", meta.colume, meta.size)
         };
         let raws = match v {
-            Adjust::Add => vec![raw!("ALTER TABLE {} ADD {}", t.name, meta)],
+            Adjust::Add => {
+                let mut raws = vec![raw!("ALTER TABLE {} ADD {}", t.name, meta)];
+                if !meta.comment.is_empty() {
+                    raws.push(comment_colume(t, meta));
+                }
+                raws
+            }
             Adjust::Alter => {
                 let raw = raw!("ALTER TABLE {} ALTER COLUMN {}", t.name, meta.name);
                 let mut raws = vec![];
@@ -197,9 +335,16 @@ impl<'a> DriverMigrator<'a> for PostgresMigrator {
                 } else {
                     raws.push(raw!("{} SET DEFAULT {}", raw, meta.default));
                 }
+                raws.push(comment_colume(t, meta));
                 raws
             }
-            _ => vec![],
+            Adjust::Drop => vec![raw!("ALTER TABLE {} DROP COLUMN {}", t.name, meta.name)],
+            Adjust::Rename => vec![raw!(
+                "ALTER TABLE {} RENAME COLUMN {} TO {}",
+                t.name,
+                meta.rename,
+                meta.name
+            )],
         };
         Ok(raws)
     }
@@ -2,19 +2,42 @@ use serde::Deserialize;
 
 use crate::{
     map,
-    migrator::{ColumeMeta, IndexMeta},
-    raw, Artis, BoxFuture, IntoArtis, Result,
+    migrator::{ColumeMeta, ForeignMeta, IndexMeta},
+    raw, Artis, ArtisTx, BoxFuture, IntoArtis, Raw, Result, Value,
 };
 
-use super::{migrator::DriverMigrator, types::Adjust, TableMeta};
+use super::{
+    migrator::DriverMigrator,
+    types::{current_timestamp, Adjust},
+    TableMeta,
+};
 
 const MASTER: &'static str = "sqlite_master";
 
-#[derive(Debug)]
-pub struct SqliteMigrator {}
+const LOCK: &str = "artis_lock";
+
+// 0.3 起含自定义映射，不再支持 `SqliteMigrator {}`，使用 new() 或 default() 构造
+#[derive(Debug, Default)]
+pub struct SqliteMigrator {
+    extra: super::Mapping, // 自定义类型映射
+}
 impl SqliteMigrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 注册自定义类型映射，覆盖内置映射
+    pub fn with_mapping(extra: super::Mapping) -> Self {
+        Self { extra }
+    }
+
     fn mapping() -> super::Mapping {
-        map! {
+        #[allow(unused_mut)]
+        let mut dict = map! {
+            "i8" : "INTEGER",
+            "i16" : "INTEGER",
+            "u8" : "INTEGER",
+            "u16" : "INTEGER",
             "i32" : "INTEGER",
             "i64" : "INTEGER",
             "u32" : "INTEGER",
@@ -24,73 +47,234 @@ impl SqliteMigrator {
             "Vec" : "BLOB",
             "Map" : "BLOB",
             "bool" : "BOOLEAN",
+            "Bytes" : "BLOB",
             "String" : "TEXT",
-        }
+            "DateTime" : "TEXT",
+        };
+        #[cfg(feature = "chrono")]
+        dict.extend(map! {
+            "NaiveDate" : "TEXT",
+            "NaiveTime" : "TEXT",
+            "NaiveDateTime" : "TEXT",
+        });
+        #[cfg(feature = "uuid")]
+        dict.extend(map! {
+            "Uuid" : "TEXT",
+        });
+        #[cfg(feature = "rust_decimal")]
+        dict.extend(map! {
+            "Decimal" : "TEXT",
+        });
+        #[cfg(feature = "rbdc")]
+        dict.extend(map! {
+            "Date" : "TEXT",
+            "Time" : "TEXT",
+            "Timestamp" : "INTEGER",
+            "Decimal" : "TEXT",
+            "Uuid" : "TEXT",
+        });
+        dict
     }
 }
 
 #[derive(Debug, Deserialize)]
 struct SqliteTable {
+    pub name: String,
+    pub sql: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TableInfo {
     pub name: String,
     #[serde(rename = "type")]
     pub type_: String,
-    pub sql: String,
+    pub notnull: i64,
+    pub dflt_value: Option<String>,
+    pub pk: i64,
 }
 
-#[inline]
-fn extract_range(str: &str, p: (&str, &str)) -> String {
-    let r_idx = str.find(p.0).unwrap();
-    let l_idx = str.rfind(p.1).unwrap();
-    str[r_idx + p.0.len()..l_idx].trim().into()
+#[derive(Debug, Deserialize)]
+struct IndexList {
+    pub name: String,
+    pub unique: i64,
+    pub origin: String,
 }
 
-impl Into<TableMeta> for &SqliteTable {
-    fn into(self) -> TableMeta {
-        let mut meta = TableMeta::default();
-        meta.name = self.name.clone();
-        let raw = extract_range(&self.sql, ("(", ")"));
-        let columes: Vec<_> = raw.split(",").collect();
-        columes.iter().for_each(|v| {
-            if v.trim().starts_with("PRIMARY KEY") {
-                meta.primary = extract_range(v, ("(", ")"));
-            } else {
-                meta.columes.push(v.to_string().into());
-            }
-        });
-        meta
+#[derive(Debug, Deserialize)]
+struct IndexInfo {
+    pub seqno: i64,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForeignKey {
+    pub id: i64,
+    pub seq: i64,
+    pub table: String,
+    pub from: String,
+    pub to: Option<String>,
+}
+
+impl From<&TableInfo> for ColumeMeta {
+    fn from(v: &TableInfo) -> Self {
+        ColumeMeta {
+            name: v.name.clone(),
+            colume: v.type_.clone(),
+            // INTEGER PRIMARY KEY 为 rowid 别名，PRAGMA 报告 notnull = 0，但不能为空
+            nullable: v.notnull == 0 && v.pk == 0,
+            default: current_timestamp(v.dflt_value.clone().unwrap_or_default()),
+            ..Default::default()
+        }
     }
 }
 
-impl Into<ColumeMeta> for String {
-    fn into(self) -> ColumeMeta {
-        let mut meta = ColumeMeta::default();
-        let mut itr = self.split_whitespace();
-        meta.name = itr.next().unwrap().into();
-        meta.colume = itr.next().unwrap().into();
-        meta.nullable = true;
-        while let Some(v) = itr.next() {
-            match v {
-                "NOT" => {
-                    meta.nullable = false;
-                    itr.next();
-                }
-                "DEFAULT" => {
-                    meta.default = itr.next().unwrap().into();
+#[inline]
+fn pragma(name: &str, v: &str) -> (String, Vec<Value>) {
+    (
+        raw!("PRAGMA {}(\"{}\")", name, v.replace('"', "\"\"")),
+        vec![],
+    )
+}
+
+// 拆分建表语句中的字段定义，忽略注释与引号内的内容
+fn colume_defs(sql: &str) -> Vec<Vec<String>> {
+    let (mut list, mut words, mut word) = (vec![], vec![], String::new());
+    let (mut depth, mut chars) = (0, sql.chars().peekable());
+    while let Some(c) = chars.next() {
+        let end = match c {
+            '-' if chars.peek() == Some(&'-') => {
+                chars.find(|v| *v == '\n');
+                true
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                chars.find(|v| std::mem::replace(&mut last, *v) == '*' && *v == '/');
+                true
+            }
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let quoted: String = chars.by_ref().take_while(|v| *v != close).collect();
+                // 引号内的标识符作为字段名
+                if depth == 1 && c != '\'' {
+                    word.push_str(&quoted);
                 }
-                "COMMENT" => {
-                    meta.comment = itr.next().unwrap().into();
+                false
+            }
+            '(' | ')' | ',' => {
+                depth += match c {
+                    '(' => 1,
+                    ')' => -1,
+                    _ => 0,
+                };
+                true
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                if depth == 1 {
+                    word.push(c);
                 }
-                _ => {}
-            };
+                false
+            }
+            _ => true,
+        };
+        if end && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if (c == ',' && depth == 1) || (c == ')' && depth == 0) {
+            list.push(std::mem::take(&mut words));
         }
-        meta
     }
+    list
+}
+
+// AUTOINCREMENT 只能用于单字段 INTEGER PRIMARY KEY，在该字段的定义中查找
+fn is_autoincrement(sql: &str, primary: &str) -> bool {
+    colume_defs(sql).iter().any(|words| {
+        words
+            .first()
+            .is_some_and(|v| v.eq_ignore_ascii_case(primary))
+            && words
+                .iter()
+                .any(|v| v.eq_ignore_ascii_case("AUTOINCREMENT"))
+    })
+}
+
+async fn fetch_table<R: IntoArtis>(rb: &R, table: &SqliteTable) -> Result<TableMeta> {
+    let mut meta = TableMeta {
+        name: table.name.clone(),
+        ..Default::default()
+    };
+    let list: Vec<TableInfo> = rb.fetch(&pragma("table_info", &table.name)).await?;
+    let mut primary: Vec<_> = list.iter().filter(|v| v.pk > 0).collect();
+    primary.sort_by_key(|v| v.pk);
+    meta.primary = primary
+        .iter()
+        .map(|v| v.name.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let increment = primary.len() == 1 && is_autoincrement(&table.sql, &meta.primary);
+    for v in list.iter() {
+        let mut cmeta: ColumeMeta = v.into();
+        cmeta.increment = increment && v.pk > 0;
+        meta.columes.push(cmeta);
+    }
+
+    let list: Vec<IndexList> = rb.fetch(&pragma("index_list", &table.name)).await?;
+    for v in list.iter().filter(|v| v.origin != "pk") {
+        let mut info: Vec<IndexInfo> = rb.fetch(&pragma("index_info", &v.name)).await?;
+        info.sort_by_key(|v| v.seqno);
+        // 表达式索引没有字段名，无法还原
+        let Some(columns) = info.into_iter().map(|v| v.name).collect::<Option<Vec<_>>>() else {
+            continue;
+        };
+        let column = columns.join(",");
+        let inx = if v.unique == 1 {
+            IndexMeta::Unique(column)
+        } else {
+            IndexMeta::Index(column)
+        };
+        meta.indexs.push(inx);
+    }
+
+    let mut list: Vec<ForeignKey> = rb.fetch(&pragma("foreign_key_list", &table.name)).await?;
+    list.sort_by_key(|v| (v.id, v.seq));
+    for v in list.iter() {
+        let refer = v.to.clone().unwrap_or_default();
+        match meta.foreigns.last_mut() {
+            Some(f) if v.seq > 0 => {
+                f.colume.push_str(&raw!(",{}", v.from));
+                f.refer.push_str(&raw!(",{}", refer));
+            }
+            _ => meta.foreigns.push(ForeignMeta {
+                colume: v.from.clone(),
+                table: v.table.clone(),
+                refer,
+            }),
+        }
+    }
+    Ok(meta)
+}
+
+async fn fetch_tables<R: IntoArtis>(rb: &R) -> Result<Vec<TableMeta>> {
+    let raw = Raw::table(MASTER)
+        .select(vec!["name", "sql"])
+        .where_("type = 'table' AND name NOT LIKE 'sqlite_%'", vec![])
+        .order("name");
+    let list: Vec<SqliteTable> = rb.fetch(&raw).await?;
+    let mut metas: Vec<TableMeta> = vec![];
+    for v in list.iter() {
+        metas.push(fetch_table(rb, v).await?);
+    }
+    Ok(metas)
 }
 
 impl<'a> DriverMigrator<'a> for SqliteMigrator {
     fn mapping(&self, meta: &mut TableMeta) {
-        let dict = SqliteMigrator::mapping();
+        let dict = self.types();
+        // SQLite 不支持注释，忽略以免每次迁移都产生变更
+        meta.comment.clear();
         meta.columes.iter_mut().for_each(|v: &mut ColumeMeta| {
+            v.comment.clear();
             if !v.colume.starts_with(":") {
                 return;
             }
@@ -102,37 +286,35 @@ impl<'a> DriverMigrator<'a> for SqliteMigrator {
         });
     }
 
+    fn transactional(&self) -> bool {
+        true
+    }
+
+    // 建表是事务中的第一条写入，其他连接在获得写锁前等待，不会因读锁升级而死锁
+    // 锁表在同一事务中删除，异常回滚时不会残留
+    fn lock(&self) -> Result<Vec<String>> {
+        Ok(vec![raw!("CREATE TABLE {} (id INTEGER PRIMARY KEY)", LOCK)])
+    }
+
+    fn unlock(&self) -> Result<Vec<String>> {
+        Ok(vec![raw!("DROP TABLE {}", LOCK)])
+    }
+
+    fn types(&self) -> super::Mapping {
+        let mut dict = SqliteMigrator::mapping();
+        dict.extend(self.extra.iter());
+        dict
+    }
+
     fn fetch_tables(&self, rb: &'a Artis) -> BoxFuture<'a, Result<Vec<TableMeta>>> {
-        Box::pin(async move {
-            let mut metas: Vec<TableMeta> = vec![];
-            let raw = (MASTER, ("sql NOT NULL", vec![]), "`type` DESC");
-            let list: Vec<SqliteTable> = rb.fetch(&raw).await?;
-            let tables: Vec<_> = list.iter().filter(|v| v.type_ == "table").collect();
-            for v in tables {
-                if v.name.starts_with("sqlite_") {
-                    continue;
-                }
-                metas.push(v.into());
-            }
-            let indexs: Vec<_> = list.iter().filter(|v| v.type_ == "index").collect();
-            for v in indexs {
-                let table = extract_range(&v.sql, ("ON", "("));
-                let colume = extract_range(&v.sql, ("(", ")"));
-                for meta in metas.iter_mut() {
-                    if meta.name == table {
-                        if v.sql.contains("UNIQUE") {
-                            meta.indexs.push(IndexMeta::Unique(colume));
-                        } else if v.sql.contains("INDEX") {
-                            meta.indexs.push(IndexMeta::Index(colume));
-                        } else {
-                            continue;
-                        }
-                        break;
-                    }
-                }
-            }
-            Ok(metas)
-        })
+        Box::pin(fetch_tables(rb))
+    }
+
+    fn fetch_tables_tx<'b>(
+        &'b self,
+        rb: &'b ArtisTx,
+    ) -> Option<BoxFuture<'b, Result<Vec<TableMeta>>>> {
+        Some(Box::pin(fetch_tables(rb)))
     }
 
     fn create_table(&self, meta: &TableMeta) -> Result<String> {
@@ -151,10 +333,25 @@ impl<'a> DriverMigrator<'a> for SqliteMigrator {
     }
 
     fn colume_raw(&self, t: &TableMeta, v: Adjust, meta: &ColumeMeta) -> Result<Vec<String>> {
-        if let Adjust::Add = v {
-            return Ok(vec![raw!("ALTER TABLE {} ADD {}", t.name, meta)]);
-        }
-        panic!("This isn't supported at : {}.{:#?}", t.name, meta)
+        let raw = match v {
+            Adjust::Add => raw!("ALTER TABLE {} ADD {}", t.name, meta),
+            Adjust::Drop => raw!("ALTER TABLE {} DROP COLUMN {}", t.name, meta.name),
+            Adjust::Rename => raw!(
+                "ALTER TABLE {} RENAME COLUMN {} TO {}",
+                t.name,
+                meta.rename,
+                meta.name
+            ),
+            Adjust::Alter => {
+                let msg = raw!(
+                    "sqlite does not support altering column {}.{}",
+                    t.name,
+                    meta.name
+                );
+                return Err(msg.into());
+            }
+        };
+        Ok(vec![raw])
     }
 
     fn create_index(&self, t: &TableMeta, meta: &IndexMeta) -> Result<String> {
@@ -1,8 +1,12 @@
+mod generator;
 mod types;
 
+pub mod migration;
 pub mod migrator;
-pub use migrator::{ArtisMigrator, DriverMigrator};
-pub use types::{Adjust, ColumeMeta, IndexMeta, Mapping, TableMeta};
+pub use generator::generate;
+pub use migration::{Migration, MigrationState, MigrationStatus, Step};
+pub use migrator::{diff, ArtisMigrator, DriverMigrator};
+pub use types::{Adjust, Change, ColumeMeta, ForeignMeta, IndexMeta, Mapping, Plan, TableMeta};
 
 #[cfg(feature = "sqlite")]
 mod migrator_sqlite;
@@ -21,3 +25,9 @@ mod migrator_postgres;
 
 #[cfg(feature = "postgres")]
 pub use migrator_postgres::PostgresMigrator;
+
+#[cfg(feature = "snapshot")]
+mod snapshot;
+
+#[cfg(feature = "snapshot")]
+pub use snapshot::{from_snapshot, to_snapshot};
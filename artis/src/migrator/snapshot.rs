@@ -0,0 +1,22 @@
+use crate::Result;
+
+use super::{IndexMeta, TableMeta};
+
+// 按表名与索引排序，保证快照内容稳定
+fn stable(metas: &[TableMeta]) -> Vec<TableMeta> {
+    let mut list = metas.to_vec();
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    list.iter_mut().for_each(|v| {
+        v.indexs
+            .sort_by_key(|v| (v.column(), matches!(v, IndexMeta::Unique(_))))
+    });
+    list
+}
+
+pub fn to_snapshot(metas: &[TableMeta]) -> Result<String> {
+    serde_json::to_string_pretty(&stable(metas)).map_err(|e| e.to_string().into())
+}
+
+pub fn from_snapshot(raw: &str) -> Result<Vec<TableMeta>> {
+    serde_json::from_str(raw).map_err(|e| e.to_string().into())
+}
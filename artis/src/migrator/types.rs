@@ -1,10 +1,12 @@
 use std::{collections::HashMap, fmt::Display};
 
+use serde::{Deserialize, Serialize};
+
 use crate::raw;
 
 pub type Mapping = HashMap<&'static str, &'static str>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 // 考虑多字段索引
 pub enum IndexMeta {
     Index(String),
@@ -28,14 +30,81 @@ impl IndexMeta {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+// 外键仅由数据库读取，不参与迁移
+pub struct ForeignMeta {
+    pub colume: String, // 字段，多字段以逗号分隔
+    pub table: String,  // 关联表
+    pub refer: String,  // 关联字段
+}
+
 #[derive(Debug, Clone)]
 pub enum Adjust {
     Add,
     Drop,
     Alter,
+    Rename,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum Change {
+    CreateTable,
+    AddColume(String),
+    AlterColume(String),
+    RenameColume(String, String),
+    DropColume(String),
+    CreateIndex(String),
+    DropIndex(String),
+    CommentTable,
+    CommentColume(String),
+}
+
+impl Change {
+    // 可能造成数据丢失的变更
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, Change::AlterColume(_) | Change::DropColume(_))
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::CreateTable => write!(f, "create table"),
+            Change::AddColume(v) => write!(f, "add column {}", v),
+            Change::AlterColume(v) => write!(f, "alter column {}", v),
+            Change::RenameColume(o, v) => write!(f, "rename column {} to {}", o, v),
+            Change::DropColume(v) => write!(f, "drop column {}", v),
+            Change::CreateIndex(v) => write!(f, "create index {}", v),
+            Change::DropIndex(v) => write!(f, "drop index {}", v),
+            Change::CommentTable => write!(f, "comment table"),
+            Change::CommentColume(v) => write!(f, "comment column {}", v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Plan {
+    pub table: String,     // 表名
+    pub change: Change,    // 变更
+    pub raws: Vec<String>, // 执行语句
+    pub destructive: bool, // 是否破坏性变更
+}
+
+impl Plan {
+    pub fn new(table: &str, change: Change, raws: Vec<String>) -> Self {
+        let destructive = change.is_destructive();
+        Self {
+            table: table.into(),
+            change,
+            raws,
+            destructive,
+        }
+    }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ColumeMeta {
     pub name: String,    // 字段
     pub size: usize,     // 大小
@@ -44,6 +113,7 @@ pub struct ColumeMeta {
     pub default: String, // 默认值
     pub comment: String, // 注释
     pub increment: bool, // 自增
+    pub rename: String,  // 原字段名
 }
 
 impl PartialEq for ColumeMeta {
@@ -54,11 +124,18 @@ impl PartialEq for ColumeMeta {
         {
             return false;
         }
-        let colume = format!("{}({})", self.colume, self.size);
-        if self.size != 0 && colume != other.colume {
+        let sized = |v: &ColumeMeta| {
+            if v.size == 0 {
+                v.colume.clone()
+            } else {
+                format!("{}({})", v.colume, v.size)
+            }
+        };
+        let (colume, other) = (sized(self), sized(other));
+        if self.size != 0 && colume != other {
             return false;
         }
-        other.colume.starts_with(&self.colume)
+        other.starts_with(&colume)
     }
 }
 
@@ -78,12 +155,30 @@ impl Display for ColumeMeta {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TableMeta {
-    pub name: String,             // 表名
-    pub primary: String,          // 主键字段  考虑复合主键
-    pub indexs: Vec<IndexMeta>,   // 索引
-    pub columes: Vec<ColumeMeta>, // 字段
+    pub name: String,               // 表名
+    pub comment: String,            // 注释
+    pub primary: String,            // 主键字段  考虑复合主键
+    pub indexs: Vec<IndexMeta>,     // 索引
+    pub columes: Vec<ColumeMeta>,   // 字段
+    pub prune: bool,                // 删除未声明的字段
+    pub foreigns: Vec<ForeignMeta>, // 外键
+}
+
+// 转为 SQL 字符串字面量
+#[cfg(any(feature = "mysql", feature = "postgres"))]
+pub(crate) fn quote_literal(v: &str) -> String {
+    raw!("'{}'", v.replace('\'', "''"))
+}
+
+// 统一当前时间默认值的写法，如 now()、current_timestamp()
+pub(crate) fn current_timestamp(v: String) -> String {
+    match v.to_lowercase().as_str() {
+        "now()" | "current_timestamp" | "current_timestamp()" => "CURRENT_TIMESTAMP".into(),
+        _ => v,
+    }
 }
 
 // impl TableMeta {
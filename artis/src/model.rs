@@ -0,0 +1,360 @@
+use std::{fmt::Display, future::Future, marker::PhantomData};
+
+use rbatis::rbdc::types::DateTime;
+use rbs::value::map::ValueMap;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{into_raw::IntoColumn, raw, relation::Eager, Error, IntoArtis, Raw, Result, Value};
+
+// 模型生命周期钩子，返回错误时中止操作
+// #[derive(Artis)] 默认生成空实现，结构体标记 #[artis(hooks)] 时需自行实现
+pub trait Hooks {
+    fn before_insert(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_insert(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn before_update(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_update(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn before_delete(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_delete(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn after_fetch(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// 由 #[derive(Artis)] 生成，提供基于主键的增删改查
+pub trait Model: Hooks + Send + Sync + Sized {
+    fn table() -> &'static str;
+
+    fn primary() -> &'static str;
+
+    // #[artis(created_at)] 标记的字段，插入时自动填充
+    fn created_at() -> Option<&'static str> {
+        None
+    }
+
+    // #[artis(updated_at)] 标记的字段，插入与更新时自动填充
+    fn updated_at() -> Option<&'static str> {
+        None
+    }
+
+    // #[artis(soft_delete)] 标记的字段，删除时写入删除时间
+    fn soft_delete() -> Option<&'static str> {
+        None
+    }
+
+    // #[artis(version)] 标记的字段，更新时校验并递增版本号
+    fn version() -> Option<&'static str> {
+        None
+    }
+
+    // 版本号加一，由 #[derive(Artis)] 生成
+    fn increase_version(&mut self) {}
+
+    // 插入后写回自增主键，由 #[derive(Artis)] 为 PRIMARY, AUTO_INCREMENT 字段生成
+    fn set_increment(&mut self, _id: Value) -> Result<()> {
+        Ok(())
+    }
+
+    // 关联字段，#[artis(has_many | has_one | belongs_to)] 标记，不写入表
+    fn relations() -> &'static [&'static str] {
+        &[]
+    }
+
+    // 加载关联，由 #[derive(Artis)] 生成
+    fn load(
+        rb: &impl IntoArtis,
+        name: &str,
+        list: &mut [Self],
+    ) -> impl Future<Output = Result<()>> {
+        let _ = (rb, list);
+        let name = name.to_string();
+        async move { Err(raw!("{} has no relation {}", Self::table(), name).into()) }
+    }
+
+    // 预加载关联，如 Person::with("orders").all(rb)
+    fn with(name: &str) -> Eager<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        Eager::new().with(name)
+    }
+
+    // 查询构造器，软删除模型默认排除已删除的记录
+    fn query() -> Raw {
+        let raw = Raw::table(Self::table());
+        match Self::soft_delete() {
+            Some(column) => raw.soft_delete(column),
+            None => raw,
+        }
+    }
+
+    fn find_by_id(
+        rb: &impl IntoArtis,
+        id: impl Into<Value>,
+    ) -> impl Future<Output = Result<Option<Self>>>
+    where
+        Self: DeserializeOwned,
+    {
+        let id = id.into();
+        async move {
+            let raw = Self::query()
+                .where_(&raw!("{} = ?", primary::<Self>()?), vec![id])
+                .limit(1);
+            let list: Vec<Self> = rb.fetch(&raw).await?;
+            Ok(fetched(list)?.into_iter().next())
+        }
+    }
+
+    fn all(rb: &impl IntoArtis) -> impl Future<Output = Result<Vec<Self>>>
+    where
+        Self: DeserializeOwned,
+    {
+        async move { fetched(rb.fetch(&Self::query()).await?) }
+    }
+
+    // 空值字段不写入，返回自增 ID 并写回自增主键
+    fn insert(&mut self, rb: &impl IntoArtis) -> impl Future<Output = Result<Value>>
+    where
+        Self: Serialize,
+    {
+        async move {
+            self.before_insert()?;
+            let mut value = model_value(self)?;
+            stamp(&mut value, Self::created_at(), true)?;
+            stamp(&mut value, Self::updated_at(), false)?;
+            let raw = Raw::table(Self::table()).values(value);
+            let id = rb.saving(&raw).await?;
+            if !id.is_null() {
+                self.set_increment(id.clone())?;
+            }
+            self.after_insert()?;
+            Ok(id)
+        }
+    }
+
+    // 按主键更新其余全部字段，创建时间不会被覆盖
+    // 带版本号的模型在版本不一致时返回 Error::StaleObject，成功后递增版本号
+    fn update_by_pk(&mut self, rb: &impl IntoArtis) -> impl Future<Output = Result<u64>>
+    where
+        Self: Serialize,
+    {
+        async move {
+            self.before_update()?;
+            let primary = primary::<Self>()?;
+            let mut value = model_value(self)?;
+            if let (Some(column), Value::Map(dict)) = (Self::created_at(), &mut value) {
+                dict.remove(&Value::String(column.into()));
+            }
+            stamp(&mut value, Self::updated_at(), false)?;
+            let id = primary_value(&value, primary)?;
+            if let Value::Map(dict) = &mut value {
+                dict.remove(&Value::String(primary.into()));
+            }
+            let Some(version) = Self::version() else {
+                let raw = Raw::table(Self::table())
+                    .values(value)
+                    .where_(&raw!("{} = ?", primary), vec![id]);
+                let line = rb.update(&raw).await?;
+                self.after_update()?;
+                return Ok(line);
+            };
+            let current = field(&value, version);
+            if let Value::Map(dict) = &mut value {
+                dict.remove(&Value::String(version.into()));
+            }
+            let msg = raw!(
+                "{} {} = {} {} = {}",
+                Self::table(),
+                primary,
+                id,
+                version,
+                current
+            );
+            let raw = Raw::table(Self::table())
+                .values(value)
+                .increase(version)
+                .where_(
+                    &raw!("{} = ? AND {} = ?", primary, version),
+                    vec![id, current],
+                );
+            let line = rb.update(&raw).await?;
+            if line == 0 {
+                return Err(Error::StaleObject(msg));
+            }
+            self.increase_version();
+            self.after_update()?;
+            Ok(line)
+        }
+    }
+
+    // 软删除模型写入删除时间，否则删除记录
+    fn delete(&self, rb: &impl IntoArtis) -> impl Future<Output = Result<u64>>
+    where
+        Self: Serialize,
+    {
+        async move {
+            let Some(column) = Self::soft_delete() else {
+                return self.force_delete(rb).await;
+            };
+            self.before_delete()?;
+            let primary = primary::<Self>()?;
+            let id = primary_value(&to_value(self)?, primary)?;
+            let mut value = Value::Map(ValueMap::new());
+            stamp(&mut value, Some(column), false)?;
+            let raw = Self::query()
+                .values(value)
+                .where_(&raw!("{} = ?", primary), vec![id]);
+            let line = rb.update(&raw).await?;
+            self.after_delete()?;
+            Ok(line)
+        }
+    }
+
+    // 删除记录，忽略软删除
+    fn force_delete(&self, rb: &impl IntoArtis) -> impl Future<Output = Result<u64>>
+    where
+        Self: Serialize,
+    {
+        async move {
+            self.before_delete()?;
+            let primary = primary::<Self>()?;
+            let id = primary_value(&to_value(self)?, primary)?;
+            let raw = Raw::table(Self::table()).where_(&raw!("{} = ?", primary), vec![id]);
+            let line = rb.delete(&raw).await?;
+            self.after_delete()?;
+            Ok(line)
+        }
+    }
+}
+
+pub(crate) fn fetched<M: Model>(mut list: Vec<M>) -> Result<Vec<M>> {
+    for v in list.iter_mut() {
+        v.after_fetch()?;
+    }
+    Ok(list)
+}
+
+pub(crate) fn primary<M: Model>() -> Result<&'static str> {
+    if M::primary().is_empty() {
+        return Err(raw!("{} has no primary key", M::table()).into());
+    }
+    Ok(M::primary())
+}
+
+pub(crate) fn to_value<T: Serialize>(v: &T) -> Result<Value> {
+    rbs::value(v).map_err(|e| e.to_string().into())
+}
+
+// 供 #[derive(Artis)] 生成的代码将数据库返回值转为字段类型
+pub fn from_value<T: DeserializeOwned>(v: Value) -> Result<T> {
+    rbs::from_value(v).map_err(|e| e.to_string().into())
+}
+
+// 写入当前时间，keep 为 true 时保留已有的值
+fn stamp(v: &mut Value, column: Option<&str>, keep: bool) -> Result<()> {
+    let (Some(column), Value::Map(dict)) = (column, v) else {
+        return Ok(());
+    };
+    let key = Value::String(column.into());
+    if keep && !dict.get(&key).is_null() {
+        return Ok(());
+    }
+    dict.insert(key, to_value(&DateTime::now())?);
+    Ok(())
+}
+
+// 模型的字段值，不包含关联字段
+fn model_value<M: Model + Serialize>(v: &M) -> Result<Value> {
+    let mut value = to_value(v)?;
+    if let Value::Map(dict) = &mut value {
+        for v in M::relations() {
+            dict.remove(&Value::String(v.to_string()));
+        }
+    }
+    Ok(value)
+}
+
+pub(crate) fn field(v: &Value, column: &str) -> Value {
+    v.as_map()
+        .and_then(|v| v.0.get(&Value::String(column.into())))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+fn primary_value(v: &Value, primary: &str) -> Result<Value> {
+    let id = field(v, primary);
+    if id.is_null() {
+        return Err(raw!("primary key {} is null", primary).into());
+    }
+    Ok(id)
+}
+
+// 模型字段，由 #[derive(Artis)] 生成，字段改名后引用处编译失败
+pub struct Column<M, T> {
+    name: &'static str,
+    _marker: PhantomData<fn() -> (M, T)>,
+}
+
+impl<M, T> Column<M, T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn asc(&self) -> String {
+        raw!("{} ASC", self.name)
+    }
+
+    pub fn desc(&self) -> String {
+        raw!("{} DESC", self.name)
+    }
+}
+
+impl<M, T> Clone for Column<M, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M, T> Copy for Column<M, T> {}
+
+impl<M, T> std::fmt::Debug for Column<M, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Column({})", self.name)
+    }
+}
+
+impl<M, T> Display for Column<M, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl<M, T> IntoColumn for Column<M, T> {
+    fn column(&self) -> String {
+        self.name.into()
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    model::{fetched, field, primary, to_value},
+    IntoArtis, IntoRaw, Model, Result, Value,
+};
+
+// 预加载关联，按外键批量查询后回填到每个模型
+pub struct Eager<M> {
+    relations: Vec<String>,
+    _marker: PhantomData<fn() -> M>,
+}
+
+impl<M: Model + DeserializeOwned> Eager<M> {
+    pub fn new() -> Self {
+        Self {
+            relations: vec![],
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn with(mut self, name: &str) -> Self {
+        self.relations.push(name.into());
+        self
+    }
+
+    pub async fn fetch(&self, rb: &impl IntoArtis, raw: &dyn IntoRaw) -> Result<Vec<M>> {
+        let mut list = fetched(rb.fetch(raw).await?)?;
+        self.load(rb, &mut list).await?;
+        Ok(list)
+    }
+
+    pub async fn all(&self, rb: &impl IntoArtis) -> Result<Vec<M>> {
+        self.fetch(rb, &M::query()).await
+    }
+
+    // 为已查询的模型加载关联
+    pub async fn load(&self, rb: &impl IntoArtis, list: &mut [M]) -> Result<()> {
+        if list.is_empty() {
+            return Ok(());
+        }
+        for name in self.relations.iter() {
+            M::load(rb, name, list).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<M: Model + DeserializeOwned> Default for Eager<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 子表外键指向当前模型主键
+pub async fn has_many<P, C>(
+    rb: &impl IntoArtis,
+    list: &mut [P],
+    foreign_key: &str,
+    set: impl Fn(&mut P, Vec<C>),
+) -> Result<()>
+where
+    P: Model + Serialize,
+    C: Model + DeserializeOwned,
+{
+    let keys = keys(list, primary::<P>()?)?;
+    let mut group = related::<C>(rb, foreign_key, &keys).await?;
+    for (v, key) in list.iter_mut().zip(keys) {
+        let rows = group.remove(&key.to_string()).unwrap_or_default();
+        set(v, decode(rows)?);
+    }
+    Ok(())
+}
+
+pub async fn has_one<P, C>(
+    rb: &impl IntoArtis,
+    list: &mut [P],
+    foreign_key: &str,
+    set: impl Fn(&mut P, Option<C>),
+) -> Result<()>
+where
+    P: Model + Serialize,
+    C: Model + DeserializeOwned,
+{
+    has_many(rb, list, foreign_key, |v, rows: Vec<C>| {
+        set(v, rows.into_iter().next())
+    })
+    .await
+}
+
+// 当前模型外键指向关联模型主键
+pub async fn belongs_to<P, C>(
+    rb: &impl IntoArtis,
+    list: &mut [P],
+    foreign_key: &str,
+    set: impl Fn(&mut P, Option<C>),
+) -> Result<()>
+where
+    P: Model + Serialize,
+    C: Model + DeserializeOwned,
+{
+    let keys = keys(list, foreign_key)?;
+    let group = related::<C>(rb, primary::<C>()?, &keys).await?;
+    for (v, key) in list.iter_mut().zip(keys) {
+        // 多个模型可能关联同一条记录
+        let rows = group.get(&key.to_string()).cloned().unwrap_or_default();
+        set(v, decode(rows)?.into_iter().next());
+    }
+    Ok(())
+}
+
+fn keys<P: Serialize>(list: &[P], column: &str) -> Result<Vec<Value>> {
+    list.iter()
+        .map(|v| Ok(field(&to_value(v)?, column)))
+        .collect()
+}
+
+// 按字段分组，不同整数类型的值以字符串形式比较
+async fn related<C: Model>(
+    rb: &impl IntoArtis,
+    column: &str,
+    keys: &[Value],
+) -> Result<HashMap<String, Vec<Value>>> {
+    let mut args: Vec<Value> = vec![];
+    let mut seen: HashSet<String> = HashSet::new();
+    for v in keys.iter().filter(|v| !v.is_null()) {
+        if seen.insert(v.to_string()) {
+            args.push(v.clone());
+        }
+    }
+    let mut group: HashMap<String, Vec<Value>> = HashMap::new();
+    if args.is_empty() {
+        return Ok(group);
+    }
+    let rows: Vec<Value> = rb.fetch(&C::query().where_in(column, args)).await?;
+    for row in rows {
+        let key = field(&row, column).to_string();
+        group.entry(key).or_default().push(row);
+    }
+    Ok(group)
+}
+
+fn decode<C: Model + DeserializeOwned>(rows: Vec<Value>) -> Result<Vec<C>> {
+    let list = rows
+        .into_iter()
+        .map(|v| rbs::from_value(v).map_err(|e| e.to_string().into()))
+        .collect::<Result<Vec<C>>>()?;
+    fetched(list)
+}
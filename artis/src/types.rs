@@ -41,6 +41,7 @@ pub struct ExecResult {
     pub last_insert_id: crate::Value,
 }
 
+#[derive(Debug, Clone, Copy)]
 pub enum RawType {
     Fetch,
     Saving,